@@ -186,6 +186,19 @@ impl Easel {
         self.pause.root()
     }
 
+    /// Returns the canvas element, so that callers can listen for its events.
+    #[must_use]
+    pub fn canvas(&self) -> HtmlCanvasElement {
+        self.cell.borrow().canvas.clone()
+    }
+
+    /// Returns the caption element, so that callers can update it while the
+    /// animation is paused.
+    #[must_use]
+    pub fn caption(&self) -> Element {
+        self.cell.borrow().caption.clone()
+    }
+
     #[must_use]
     pub fn is_paused(&self) -> bool {
         self.pause.is_paused()
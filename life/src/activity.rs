@@ -0,0 +1,140 @@
+//! Where cells have recently died, and where they change state most often.
+
+use std::collections::HashMap;
+
+use crate::universe::{Bounds, Cell, Point};
+
+/// The number of generations for which a dead cell leaves a trail.
+pub const TRAIL_LEN: u8 = 16;
+
+/// The number of generations after which heat halves. Cells that cool to zero
+/// are forgotten, so that guns and spaceships don't leave heat behind forever.
+const HEAT_HALF_LIFE: u32 = 64;
+
+#[derive(Default)]
+pub struct Activity {
+    /// Generations since each recently dead cell died.
+    trails: HashMap<Point, u8>,
+    /// The number of times each cell has changed state, halved every
+    /// [`HEAT_HALF_LIFE`] generations.
+    heat: HashMap<Point, u32>,
+    /// The largest value in `heat`.
+    hottest: u32,
+    /// Generations since heat last halved.
+    cooling: u32,
+}
+
+impl Activity {
+    pub fn new() -> Activity {
+        Activity::default()
+    }
+
+    /// Ages existing trails. Call this once per generation, before recording
+    /// that generation's changes.
+    pub fn fade(&mut self) {
+        self.trails.retain(|_, age| {
+            *age += 1;
+            *age < TRAIL_LEN
+        });
+        self.cooling += 1;
+        if self.cooling == HEAT_HALF_LIFE {
+            self.cooling = 0;
+            self.heat.retain(|_, heat| {
+                *heat /= 2;
+                *heat > 0
+            });
+            self.hottest = self.heat.values().copied().max().unwrap_or_default();
+        }
+    }
+
+    /// Records a change of state.
+    pub fn record(&mut self, p: Point, c: Cell) {
+        if c == Cell::DEAD {
+            self.trails.insert(p, 0);
+        } else {
+            self.trails.remove(&p);
+        }
+        let heat = self.heat.entry(p).or_default();
+        *heat += 1;
+        self.hottest = self.hottest.max(*heat);
+    }
+
+    /// Returns the trails within the specified bounds, with their ages.
+    pub fn trails_within(&self, bounds: Bounds) -> impl Iterator<Item = (Point, u8)> {
+        self.trails
+            .iter()
+            .map(|(&p, &age)| (p, age))
+            .filter(move |&(p, _)| bounds.contains(p))
+    }
+
+    /// Returns the cells within the specified bounds that have recently
+    /// changed, with their heat.
+    pub fn heat_within(&self, bounds: Bounds) -> impl Iterator<Item = (Point, u32)> {
+        self.heat
+            .iter()
+            .map(|(&p, &heat)| (p, heat))
+            .filter(move |&(p, _)| bounds.contains(p))
+    }
+
+    pub fn hottest(&self) -> u32 {
+        self.hottest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trails_fade_and_heat_accumulates() {
+        let p = Point { i: 1, j: 2 };
+        let everywhere = Bounds {
+            min: Point { i: -9, j: -9 },
+            max: Point { i: 9, j: 9 },
+        };
+        let mut activity = Activity::new();
+        activity.record(p, Cell::LIVE);
+        activity.fade();
+        activity.record(p, Cell::DEAD);
+        assert_eq!(
+            activity.trails_within(everywhere).collect::<Vec<_>>(),
+            [(p, 0)]
+        );
+        for _ in 0..TRAIL_LEN {
+            activity.fade();
+        }
+        assert_eq!(activity.trails_within(everywhere).count(), 0);
+        assert_eq!(
+            activity.heat_within(everywhere).collect::<Vec<_>>(),
+            [(p, 2)]
+        );
+        assert_eq!(activity.hottest(), 2);
+    }
+
+    #[test]
+    fn heat_cools() {
+        let everywhere = Bounds {
+            min: Point { i: -9, j: -9 },
+            max: Point { i: 9, j: 9 },
+        };
+        let (p, q) = (Point { i: 1, j: 2 }, Point { i: 3, j: 4 });
+        let mut activity = Activity::new();
+        for _ in 0..4 {
+            activity.record(p, Cell::LIVE);
+        }
+        activity.record(q, Cell::LIVE);
+        for _ in 0..HEAT_HALF_LIFE {
+            activity.fade();
+        }
+        assert_eq!(
+            activity.heat_within(everywhere).collect::<Vec<_>>(),
+            [(p, 2)]
+        );
+        assert_eq!(activity.hottest(), 2);
+        for _ in 0..2 * HEAT_HALF_LIFE {
+            activity.fade();
+        }
+        assert_eq!(activity.heat_within(everywhere).count(), 0);
+        assert_eq!(activity.hottest(), 0);
+    }
+}
@@ -0,0 +1,1392 @@
+use std::{cell::RefCell, rc::Rc};
+
+use wasm_bindgen::prelude::*;
+use web_sys::{
+    CanvasRenderingContext2d, ClipboardEvent, DragEvent, Element, Event, FileReader,
+    HtmlCanvasElement, HtmlSelectElement, KeyboardEvent, MouseEvent, Performance, PointerEvent,
+    WheelEvent, console,
+};
+
+use easel::{Easel, RenderContext, Result, canvas_size};
+use sugar::{Listener, prelude::*};
+use system::{SizeU32, System, f64_to_u32_saturating};
+
+use crate::activity::Activity;
+use crate::catalog::{CATALOG, Category};
+use crate::census::{self, Classifier, Island};
+use crate::clipboard;
+use crate::controls::{
+    CatalogControls, EdgeControls, Edges, HistoryControls, ShareControls, SoupControls,
+    SpeedControls, new_banner, new_palette, new_render_modes,
+};
+use crate::dashboard::Dashboard;
+use crate::edit::{Drag, Ghost, Line, Tool, rectangle};
+use crate::elementary::Setup;
+use crate::format;
+use crate::grid::Grid;
+use crate::history::{self, History};
+use crate::pattern::Pattern;
+use crate::shade::{
+    AGE_COLORS, HEAT_COLORS, RenderMode, TRAIL_COLORS, age_shade, heat_shade, state_colors,
+    trail_shade,
+};
+use crate::share::{from_fragment, to_fragment};
+use crate::soup::Soup;
+use crate::spacetime;
+use crate::speed::{Pace, Speed};
+use crate::stats::Stats;
+use crate::universe::{Bounds, Cell, Point, Topology, Universe};
+use crate::viewport::Viewport;
+
+/// The smallest canvas, however small the window.
+const MIN_CANVAS_SIZE: SizeU32 = SizeU32 {
+    width: 240,
+    height: 160,
+};
+
+/// Milliseconds per frame spent running generations, leaving the rest of a
+/// typical 60 Hz frame for drawing.
+const TICK_BUDGET_MS: f64 = 12.0;
+
+/// Resizes that lose cells less than this many milliseconds apart are treated
+/// as one gesture, and undone together.
+const RESIZE_GESTURE_MS: f64 = 1000.0;
+
+/// Canvas pixels per cell, until the user zooms.
+const INITIAL_ZOOM: f64 = 2.0;
+
+const EDGE_COLOR: &str = "hsl(145, 19%, 80%)"; // Pale jade.
+
+const SELECTION_COLOR: &str = "hsl(210, 60%, 55%)"; // Blue.
+const GHOST_COLOR: &str = "hsla(145, 19%, 45%, 0.5)"; // Translucent dark jade.
+
+/// Canvas pixels per cell, from which census outlines are labeled.
+const LABEL_ZOOM: f64 = 4.0;
+const LABEL_FONT: &str = "10px sans-serif";
+
+/// The objects found in the universe, as of a revision and generation.
+struct Census {
+    islands: Vec<Island>,
+    revision: u64,
+    generation: u32,
+}
+
+/// The universe as it was before a resize lost some of its cells.
+struct Snapshot {
+    universe: Universe,
+    generation: u32,
+    /// When the most recent resize in the same gesture happened.
+    time: f64,
+}
+
+/// State shared by the easel's render callback and the event handlers.
+struct World {
+    universe: Universe,
+    viewport: Viewport,
+    generation: u32,
+    stats: Stats,
+    drag: Option<Drag>,
+    undo: Option<Snapshot>,
+    speed: Speed,
+    pace: Pace,
+    mode: RenderMode,
+    activity: Activity,
+    /// The pattern placed by the stamp tool.
+    stamp: Pattern,
+    /// The state drawn by the pencil.
+    ink: Cell,
+    history: History,
+    /// The most recent census, which is outlined until the universe changes.
+    census: Option<Census>,
+    /// The cells to copy.
+    selection: Option<Bounds>,
+    /// The pattern being pasted.
+    ghost: Option<Ghost>,
+}
+
+impl World {
+    fn new(universe: Universe, viewport: Viewport, generation: u32) -> World {
+        World {
+            universe,
+            viewport,
+            generation,
+            stats: Stats::new(),
+            drag: None,
+            undo: None,
+            speed: Speed::DEFAULT,
+            pace: Pace::new(),
+            mode: RenderMode::default(),
+            activity: Activity::new(),
+            stamp: CATALOG[0].pattern(),
+            ink: Cell::LIVE,
+            history: History::new(history::DEFAULT_LIMIT),
+            census: None,
+            selection: None,
+            ghost: None,
+        }
+    }
+
+    /// Replaces the universe, and forgets everything about the old one except
+    /// its rule and topology. On a torus, the origin of the new universe is
+    /// centered.
+    fn reset(&mut self, mut universe: Universe) {
+        universe.set_rule(self.universe.rule());
+        let topology = self.universe.topology();
+        if let Some(bounds) = topology.bounds() {
+            universe.translate(Point {
+                i: bounds.size().height.cast_signed() / 2,
+                j: bounds.size().width.cast_signed() / 2,
+            });
+            universe.set_topology(topology);
+        }
+        self.load(universe, 0);
+    }
+
+    /// Replaces the universe with one loaded at the specified generation, with
+    /// its own rule and topology, and forgets everything about the old one.
+    fn load(&mut self, universe: Universe, generation: u32) {
+        self.universe = universe;
+        self.generation = generation;
+        self.stats = Stats::new();
+        self.drag = None;
+        self.activity = Activity::new();
+        self.history.clear();
+        self.census = None;
+    }
+
+    /// Returns the census, unless the universe has changed since it was taken.
+    fn current_census(&self) -> Option<&Census> {
+        self.census.as_ref().filter(|census| {
+            census.revision == self.universe.revision() && census.generation == self.generation
+        })
+    }
+
+    /// Runs one generation, recording it in the history.
+    fn step(&mut self) {
+        self.history.sync(&self.universe, self.generation);
+        let tracking = self.mode.tracks_activity();
+        if tracking {
+            self.activity.fade();
+        }
+        let activity = &mut self.activity;
+        let mut changed = Vec::new();
+        let turnover = self.universe.tick_with(|p, c| {
+            changed.push((p, c));
+            if tracking {
+                activity.record(p, c);
+            }
+        });
+        self.history.record(&self.universe, changed);
+        self.stats.record(&self.universe, turnover);
+        self.generation += 1;
+    }
+
+    /// Applies the tool to the pressed cell, at the specified canvas pixel,
+    /// perhaps starting a drag. A pasted pattern is placed instead, unless
+    /// panning.
+    fn press(&mut self, tool: Tool, p: Point, pixel: (f64, f64), erase: bool) {
+        if let Some(ghost) = self.ghost.take().filter(|_| tool != Tool::Pan) {
+            self.universe.stamp(&ghost.pattern, ghost.corner());
+            self.stats.interrupt();
+            return;
+        }
+        match tool {
+            Tool::Pan => self.drag = Some(Drag::Pan { last: pixel }),
+            Tool::Select => {
+                self.selection = Some(rectangle(p, p));
+                self.drag = Some(Drag::Select { anchor: p });
+            }
+            Tool::Stamp if !erase => {
+                self.universe.stamp(&self.stamp, self.stamp.corner_for(p));
+                self.stats.interrupt();
+            }
+            Tool::Pencil | Tool::Stamp => {
+                let paint = if erase || self.universe.at(p) == self.ink {
+                    Cell::DEAD
+                } else {
+                    self.ink
+                };
+                self.universe.set(p, paint);
+                self.stats.interrupt();
+                self.drag = Some(Drag::Paint { last: p, paint });
+            }
+        }
+    }
+
+    /// Restores an earlier generation from the history, if it is recorded.
+    /// Running on from there forgets the generations after it.
+    fn rewind(&mut self, generation: u32) {
+        if generation == self.generation {
+            return;
+        }
+        if let Some(universe) = self.history.seek(generation) {
+            self.universe = universe;
+            self.generation = generation;
+            self.stats.interrupt();
+            self.drag = None;
+            self.activity = Activity::new();
+        }
+    }
+}
+
+/// Fills the specified cells, each with the color at its index in `colors`,
+/// skipping any whose index is past the end.
+/// Cells are batched by color, since changing the fill style is slow. Square
+/// cells are filled as rectangles, and other shapes as one path per batch.
+fn fill_cells(
+    context: &CanvasRenderingContext2d,
+    viewport: &Viewport,
+    grid: Grid,
+    colors: &[impl AsRef<str>],
+    cells: impl Iterator<Item = (Point, usize)>,
+) {
+    let mut batches = vec![Vec::new(); colors.len()];
+    for (p, shade) in cells {
+        if let Some(batch) = batches.get_mut(shade) {
+            batch.push(p);
+        }
+    }
+    for (color, batch) in colors.iter().zip(batches) {
+        context.set_fill_style_str(color.as_ref());
+        if grid == Grid::Square {
+            for p in batch {
+                let (x, y, w, h) = viewport.rect(p);
+                context.fill_rect(x, y, w, h);
+            }
+            continue;
+        }
+        context.begin_path();
+        for p in batch {
+            let mut vertices = grid.outline(p).into_iter().map(|v| viewport.pixel(v));
+            if let Some((x, y)) = vertices.next() {
+                context.move_to(x, y);
+            }
+            vertices.for_each(|(x, y)| context.line_to(x, y));
+            context.close_path();
+        }
+        context.fill();
+    }
+}
+
+fn draw_cells(context: &CanvasRenderingContext2d, world: &World) {
+    let size = context.canvas().map(canvas_size).unwrap_or_default();
+    let grid = world.universe.rule().grid();
+    let visible = grid.covering(world.viewport.visible(size));
+    let World {
+        universe,
+        viewport,
+        activity,
+        ..
+    } = world;
+    // Each state but dead has its own color.
+    let colors = state_colors(universe.rule());
+    let states = || {
+        universe
+            .states_within(visible)
+            .map(|(p, c)| (p, usize::from(c.0 - 1)))
+    };
+    match world.mode {
+        RenderMode::Plain => fill_cells(context, viewport, grid, &colors, states()),
+        RenderMode::Age => {
+            let ages = universe.ages_within(visible);
+            fill_cells(
+                context,
+                viewport,
+                grid,
+                &AGE_COLORS,
+                ages.map(|(p, age)| (p, age_shade(age))),
+            );
+        }
+        RenderMode::Trails => {
+            let trails = activity.trails_within(visible);
+            let shaded = trails.map(|(p, age)| (p, trail_shade(age)));
+            fill_cells(context, viewport, grid, &TRAIL_COLORS, shaded);
+            fill_cells(context, viewport, grid, &colors, states());
+        }
+        RenderMode::Heat => {
+            let hottest = activity.hottest();
+            let heat = activity.heat_within(visible);
+            let shaded = heat.map(|(p, heat)| (p, heat_shade(heat, hottest)));
+            fill_cells(context, viewport, grid, &HEAT_COLORS, shaded);
+        }
+    }
+}
+
+/// Outlines the torus, if any, so that the user can see where cells wrap.
+fn draw_edges(context: &CanvasRenderingContext2d, world: &World) {
+    if let Some(bounds) = world.universe.topology().bounds() {
+        context.set_stroke_style_str(EDGE_COLOR);
+        let grid = world.universe.rule().grid();
+        if grid == Grid::Square {
+            let (left, top, _, _) = world.viewport.rect(bounds.min);
+            let (x, y, w, h) = world.viewport.rect(bounds.max);
+            context.stroke_rect(left - 0.5, top - 0.5, x + w - left + 1.0, y + h - top + 1.0);
+            return;
+        }
+        // Hexagonal rows are skewed, so the torus is a parallelogram.
+        let (Bounds { min, max }, bottom) = (bounds, bounds.max.i + 1);
+        let corners = [
+            (min.j, min.i, min.i),
+            (max.j + 1, min.i, min.i),
+            (max.j + 1, max.i, bottom),
+            (min.j, max.i, bottom),
+        ];
+        context.begin_path();
+        for (j, row, i) in corners {
+            let (x, y) = (f64::from(j) + grid.skew(row), f64::from(i));
+            let (x, y) = world.viewport.pixel((x, y));
+            context.line_to(x, y);
+        }
+        context.close_path();
+        context.stroke();
+    }
+}
+
+/// Returns the color outlining objects of the specified category, or unknown
+/// objects.
+fn census_color(category: Option<Category>) -> &'static str {
+    match category {
+        Some(Category::StillLife) => "hsl(210, 60%, 55%)", // Blue.
+        Some(Category::Oscillator) => "hsl(40, 80%, 50%)", // Amber.
+        Some(Category::Spaceship) => "hsl(350, 65%, 55%)", // Red.
+        _ => "hsl(0, 0%, 60%)",                            // Gray.
+    }
+}
+
+/// Outlines each island of the current census, if any, labeling the known
+/// objects when zoomed in far enough to read them.
+fn draw_census(context: &CanvasRenderingContext2d, world: &World) {
+    let Some(census) = world.current_census() else {
+        return;
+    };
+    let grid = world.universe.rule().grid();
+    let labeled = world.viewport.zoom() >= LABEL_ZOOM;
+    context.set_font(LABEL_FONT);
+    for island in &census.islands {
+        let color = census_color(island.object.map(|entry| entry.category));
+        let (x, y) = outline_box(context, world, grid, &island.cells, color);
+        if let Some(entry) = island.object.filter(|_| labeled) {
+            context.set_fill_style_str(color);
+            _ = context.fill_text(entry.name, x, y - 2.5);
+        }
+    }
+}
+
+/// Strokes a box just outside the specified cells, and returns its top left
+/// corner.
+fn outline_box(
+    context: &CanvasRenderingContext2d,
+    world: &World,
+    grid: Grid,
+    cells: &[Point],
+    color: &str,
+) -> (f64, f64) {
+    let (mut left, mut top) = (f64::INFINITY, f64::INFINITY);
+    let (mut right, mut bottom) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for (x, y) in cells.iter().flat_map(|&p| grid.outline(p)) {
+        (left, top) = (left.min(x), top.min(y));
+        (right, bottom) = (right.max(x), bottom.max(y));
+    }
+    let (x, y) = world.viewport.pixel((left, top));
+    let (r, b) = world.viewport.pixel((right, bottom));
+    let (x, y) = (x.round() - 1.5, y.round() - 1.5);
+    context.set_stroke_style_str(color);
+    context.stroke_rect(x, y, r - x + 1.5, b - y + 1.5);
+    (x, y)
+}
+
+/// Outlines the selection, if any, and shows the pasted pattern, if any, where
+/// it would be placed.
+fn draw_clipboard(context: &CanvasRenderingContext2d, world: &World) {
+    let grid = world.universe.rule().grid();
+    if let Some(Bounds { min, max }) = world.selection {
+        let corners = [
+            min,
+            max,
+            Point { i: min.i, j: max.j },
+            Point { i: max.i, j: min.j },
+        ];
+        outline_box(context, world, grid, &corners, SELECTION_COLOR);
+    }
+    if let Some(ghost) = &world.ghost {
+        let corner = ghost.corner();
+        let cells = ghost.pattern.states().map(|(p, _)| {
+            let p = Point {
+                i: corner.i + p.i,
+                j: corner.j + p.j,
+            };
+            (p, 0)
+        });
+        fill_cells(context, &world.viewport, grid, &[GHOST_COLOR], cells);
+    }
+}
+
+fn draw(context: &CanvasRenderingContext2d, world: &World) {
+    let size = context.canvas().map(canvas_size).unwrap_or_default();
+    context.clear_rect(0.0, 0.0, size.width.into(), size.height.into());
+    draw_edges(context, world);
+    draw_cells(context, world);
+    draw_census(context, world);
+    draw_clipboard(context, world);
+}
+
+/// Returns the canvas size that best fits the window.
+pub fn fit_canvas(system: &System) -> SizeU32 {
+    let window = &system.window;
+    let width = window.inner_width().ok().and_then(|width| width.as_f64());
+    let height = window
+        .inner_height()
+        .ok()
+        .and_then(|height| height.as_f64());
+    let (width, height) = (width.unwrap_or_default(), height.unwrap_or_default());
+    // Leave room for the page margins, and for the controls below the canvas.
+    SizeU32 {
+        width: f64_to_u32_saturating(width - 32.0).max(MIN_CANVAS_SIZE.width),
+        height: f64_to_u32_saturating(height * 0.6).max(MIN_CANVAS_SIZE.height),
+    }
+}
+
+fn now(system: &System) -> f64 {
+    system
+        .window
+        .performance()
+        .as_ref()
+        .map(Performance::now)
+        .unwrap_or_default()
+}
+
+/// Changes the topology of the universe. If that loses live cells, keeps the
+/// old universe so that the change can be undone.
+fn retopologize(world: &mut World, topology: Topology, now: f64) {
+    let before = world.universe.clone();
+    world.universe.set_topology(topology);
+    world.stats.interrupt();
+    if world.universe.population() < before.population() {
+        match &mut world.undo {
+            // Keep the state from before the first resize of the gesture.
+            Some(snapshot) if now - snapshot.time < RESIZE_GESTURE_MS => snapshot.time = now,
+            undo => {
+                *undo = Some(Snapshot {
+                    universe: before,
+                    generation: world.generation,
+                    time: now,
+                });
+            }
+        }
+    }
+}
+
+/// Adapts the world to a resized canvas. An unbounded universe keeps its
+/// center in view. A torus is scaled with the canvas, keeping its pattern
+/// centered, but never shrinks unless the edges are set to crop.
+fn resize_world(world: &mut World, edges: Edges, old: SizeU32, new: SizeU32, now: f64) {
+    let Topology::Torus(size) = world.universe.topology() else {
+        let dx = f64::from(new.width) - f64::from(old.width);
+        let dy = f64::from(new.height) - f64::from(old.height);
+        world.viewport.pan(dx / 2.0, dy / 2.0);
+        return;
+    };
+    let scale = |cells: u32, old: u32, new: u32| {
+        f64_to_u32_saturating((f64::from(cells) * f64::from(new) / f64::from(old.max(1))).round())
+    };
+    let mut fit = SizeU32 {
+        width: scale(size.width, old.width, new.width),
+        height: scale(size.height, old.height, new.height),
+    };
+    if edges == Edges::Enlarge {
+        fit.width = fit.width.max(size.width);
+        fit.height = fit.height.max(size.height);
+    }
+    if fit != size {
+        retopologize(world, Topology::Torus(fit), now);
+    }
+    if let Some(bounds) = world.universe.topology().bounds() {
+        world.viewport.center_on(bounds, new);
+    }
+}
+
+/// Returns the canvas pixel under the mouse or pointer. Event offsets are in
+/// CSS pixels, which may not match canvas pixels.
+fn canvas_pixel(canvas: &HtmlCanvasElement, event: &MouseEvent) -> (f64, f64) {
+    let scale = |offset: i32, size: u32, client: i32| {
+        f64::from(offset) * f64::from(size) / f64::from(client.max(1))
+    };
+    (
+        scale(event.offset_x(), canvas.width(), canvas.client_width()),
+        scale(event.offset_y(), canvas.height(), canvas.client_height()),
+    )
+}
+
+/// Returns a function that redraws the world if the easel is paused, because
+/// the render callback won't.
+fn redrawer(
+    easel: &Rc<RefCell<Easel>>,
+    dashboard: &Rc<Dashboard>,
+) -> impl Fn(&World) + Clone + 'static {
+    let (easel, dashboard) = (Rc::clone(easel), Rc::clone(dashboard));
+    move |world: &World| {
+        let easel = easel.borrow();
+        if easel.is_paused() {
+            easel.borrow_canvas_context(|context| draw(context, world));
+            dashboard.update(&world.universe, &world.stats);
+        }
+    }
+}
+
+/// Returns the easel's render callback, which runs whatever generations are
+/// due, then draws the world.
+fn renderer(
+    system: &System,
+    world: &Rc<RefCell<World>>,
+    dashboard: &Rc<Dashboard>,
+    history: &Rc<HistoryControls>,
+) -> impl FnMut(RenderContext) + 'static {
+    let (world, dashboard, history) = (Rc::clone(world), Rc::clone(dashboard), Rc::clone(history));
+    let clock = system.window.performance();
+    move |easel: RenderContext| {
+        let mut world = world.borrow_mut();
+        let world = &mut *world;
+        // Update state, within a time budget. Without a clock, run at most
+        // one generation per frame.
+        let now = || clock.as_ref().map_or(f64::INFINITY, Performance::now);
+        let deadline = now() + TICK_BUDGET_MS;
+        world
+            .pace
+            .accrue(world.speed, easel.delta_ms.unwrap_or_default());
+        while world.pace.take() {
+            world.step();
+            if now() > deadline {
+                world.pace.forgive();
+                break;
+            }
+        }
+        // Render the canvas and statistics.
+        draw(easel.canvas, world);
+        dashboard.update(&world.universe, &world.stats);
+        history.show(world.history.range(), world.generation);
+        // Render the caption.
+        let SizeU32 { width, height } = world
+            .universe
+            .bounds()
+            .map(|bounds| bounds.size())
+            .unwrap_or_default();
+        let generation = world.generation;
+        let caption = format!("{width}x{height} @ {generation}");
+        easel.caption.set_text_content(Some(&caption));
+    }
+}
+
+/// Listens for pointer and wheel events on the canvas, and edits the universe
+/// or moves the viewport to match.
+fn canvas_listeners(
+    world: &Rc<RefCell<World>>,
+    easel: &Rc<RefCell<Easel>>,
+    dashboard: &Rc<Dashboard>,
+    palette: HtmlSelectElement,
+) -> Result<Vec<Listener>> {
+    let canvas = easel.borrow().canvas();
+    let redraw = redrawer(easel, dashboard);
+
+    let down = {
+        let (world, canvas, redraw) = (Rc::clone(world), canvas.clone(), redraw.clone());
+        move |event: PointerEvent| {
+            let mut world = world.borrow_mut();
+            let pixel = canvas_pixel(&canvas, &event);
+            let grid = world.universe.rule().grid();
+            let p = grid.cell_at(world.viewport.position(pixel));
+            let tool = match event.button() {
+                1 => Tool::Pan, // Middle button.
+                _ => usize::try_from(palette.selected_index())
+                    .ok()
+                    .and_then(|index| Tool::ALL.get(index).copied())
+                    .unwrap_or(Tool::Pencil),
+            };
+            world.press(tool, p, pixel, event.shift_key());
+            if world.drag.is_some() {
+                _ = canvas.set_pointer_capture(event.pointer_id());
+            }
+            redraw(&world);
+            event.prevent_default();
+        }
+    };
+
+    let moved = {
+        let (world, canvas, redraw) = (Rc::clone(world), canvas.clone(), redraw.clone());
+        move |event: PointerEvent| {
+            let mut world = world.borrow_mut();
+            let World {
+                universe,
+                viewport,
+                stats,
+                drag,
+                selection,
+                ghost,
+                ..
+            } = &mut *world;
+            let pixel = canvas_pixel(&canvas, &event);
+            let p = universe.rule().grid().cell_at(viewport.position(pixel));
+            match drag {
+                Some(Drag::Paint { last, paint }) => {
+                    for q in Line::new(*last, p) {
+                        universe.set(q, *paint);
+                    }
+                    stats.interrupt();
+                    *last = p;
+                }
+                Some(Drag::Pan { last }) => {
+                    viewport.pan(pixel.0 - last.0, pixel.1 - last.1);
+                    *last = pixel;
+                }
+                Some(Drag::Select { anchor }) => *selection = Some(rectangle(*anchor, p)),
+                None => match ghost {
+                    Some(ghost) if ghost.center != p => ghost.center = p,
+                    _ => return,
+                },
+            }
+            redraw(&world);
+        }
+    };
+
+    let up = {
+        let world = Rc::clone(world);
+        move |_: PointerEvent| world.borrow_mut().drag = None
+    };
+
+    let wheel = {
+        let (world, canvas, redraw) = (Rc::clone(world), canvas.clone(), redraw.clone());
+        move |event: WheelEvent| {
+            let mut world = world.borrow_mut();
+            // A typical mouse wheel notch is 100 pixels, or about a 25% zoom.
+            let factor = 2f64.powf(-event.delta_y() / 256.0);
+            world
+                .viewport
+                .zoom_about(factor, canvas_pixel(&canvas, &event));
+            redraw(&world);
+            event.prevent_default();
+        }
+    };
+
+    let target = canvas.as_ref();
+    let mut listeners = vec![
+        Listener::new(target, "pointerdown", down)?,
+        Listener::new(target, "pointermove", moved)?,
+        Listener::new(target, "pointerup", up.clone())?,
+        Listener::new(target, "pointercancel", up)?,
+        Listener::new(target, "wheel", wheel)?,
+    ];
+    listeners.extend(clipboard_listeners(world, &canvas, redraw)?);
+    Ok(listeners)
+}
+
+/// Returns true if the event is meant for a form control, such as the rule
+/// input, rather than for the universe.
+fn is_for_control(event: &Event) -> bool {
+    let tag = event
+        .target()
+        .and_then(|target| target.dyn_into::<Element>().ok())
+        .map(|element| element.tag_name());
+    matches!(tag.as_deref(), Some("INPUT" | "SELECT" | "TEXTAREA"))
+}
+
+/// Listens for copying the selection to the clipboard, for pasting a pattern
+/// from it, and for keys that turn or flip the pasted pattern, or drop it.
+fn clipboard_listeners(
+    world: &Rc<RefCell<World>>,
+    canvas: &HtmlCanvasElement,
+    redraw: impl Fn(&World) + Clone + 'static,
+) -> Result<Vec<Listener>> {
+    let Some(document) = canvas.owner_document() else {
+        return Ok(Vec::new());
+    };
+
+    let copy = {
+        let world = Rc::clone(world);
+        move |event: ClipboardEvent| {
+            let world = world.borrow();
+            let (Some(selection), Some(data)) = (world.selection, event.clipboard_data()) else {
+                return;
+            };
+            if is_for_control(&event) {
+                return;
+            }
+            let text = clipboard::copy(&world.universe, selection);
+            if data.set_data("text/plain", &text).is_ok() {
+                event.prevent_default();
+            }
+        }
+    };
+
+    let paste = {
+        let (world, canvas, redraw) = (Rc::clone(world), canvas.clone(), redraw.clone());
+        move |event: ClipboardEvent| {
+            let text = event
+                .clipboard_data()
+                .and_then(|data| data.get_data("text/plain").ok());
+            let Some(text) = text.filter(|_| !is_for_control(&event)) else {
+                return;
+            };
+            event.prevent_default();
+            let rule = world.borrow().universe.rule();
+            match clipboard::paste(&text, rule) {
+                Ok(pattern) if pattern.states().next().is_some() => {
+                    let mut world = world.borrow_mut();
+                    let visible = world.viewport.visible(canvas_size(canvas.clone()));
+                    let center = visible.center();
+                    world.ghost = Some(Ghost { pattern, center });
+                    redraw(&world);
+                }
+                Ok(_) => (),
+                Err(err) => console::warn_1(&format!("Cannot paste pattern: {err}").into()),
+            }
+        }
+    };
+
+    let key = {
+        let world = Rc::clone(world);
+        move |event: KeyboardEvent| {
+            let mut world = world.borrow_mut();
+            let Some(ghost) = world.ghost.as_mut() else {
+                return;
+            };
+            if is_for_control(&event) || event.ctrl_key() || event.meta_key() {
+                return;
+            }
+            match event.key().as_str() {
+                "r" | "R" => ghost.pattern = ghost.pattern.rotated(),
+                "f" | "F" => ghost.pattern = ghost.pattern.flipped(),
+                "Escape" => world.ghost = None,
+                _ => return,
+            }
+            redraw(&world);
+            event.prevent_default();
+        }
+    };
+
+    Ok(vec![
+        Listener::new(document.as_ref(), "copy", copy)?,
+        Listener::new(document.as_ref(), "paste", paste)?,
+        Listener::new(document.as_ref(), "keydown", key)?,
+    ])
+}
+
+/// Shows the rule of the universe, and whether it wraps, in the controls.
+fn show_universe(share: &ShareControls, edges: &EdgeControls, universe: &Universe) {
+    share.show_rule(universe.rule());
+    edges.set_edges(match universe.topology() {
+        Topology::Plane => Edges::Unbounded,
+        Topology::Torus(_) => Edges::Enlarge,
+    });
+}
+
+/// Listens for pattern files dropped on the canvas, and loads them with their
+/// own rule and topology. Files that cannot be read or parsed are reported in
+/// the banner.
+fn drop_listeners(
+    world: &Rc<RefCell<World>>,
+    easel: &Rc<RefCell<Easel>>,
+    dashboard: &Rc<Dashboard>,
+    share: &Rc<ShareControls>,
+    edges: &Rc<EdgeControls>,
+    banner: &Element,
+) -> Result<Vec<Listener>> {
+    let canvas = easel.borrow().canvas();
+    let redraw = redrawer(easel, dashboard);
+    // One reader serves every drop, so its listeners live as long as the app.
+    let reader = Rc::new(FileReader::new()?);
+    let name = Rc::new(RefCell::new(String::new()));
+    let report = {
+        let banner = banner.clone();
+        move |message: Option<String>| {
+            banner.set_text_content(message.as_deref());
+            _ = banner.toggle_attribute_with_force("hidden", message.is_none());
+        }
+    };
+
+    let over = |event: DragEvent| {
+        if let Some(data) = event.data_transfer() {
+            data.set_drop_effect("copy");
+        }
+        event.prevent_default();
+    };
+
+    let drop = {
+        let (reader, name) = (Rc::clone(&reader), Rc::clone(&name));
+        move |event: DragEvent| {
+            event.prevent_default();
+            let file = event
+                .data_transfer()
+                .and_then(|data| data.files())
+                .and_then(|files| files.get(0));
+            if let Some(file) = file {
+                reader.abort();
+                if reader.read_as_text(&file).is_ok() {
+                    *name.borrow_mut() = file.name();
+                }
+            }
+        }
+    };
+
+    let load = {
+        let (reader, name, report) = (Rc::clone(&reader), Rc::clone(&name), report.clone());
+        let (world, share, edges) = (Rc::clone(world), Rc::clone(share), Rc::clone(edges));
+        let canvas = canvas.clone();
+        move |_: Event| {
+            let name = name.borrow();
+            let text = reader.result().ok().and_then(|result| result.as_string());
+            let loaded = text.as_deref().map(|text| format::load(&name, text));
+            match loaded {
+                Some(Ok((universe, generation))) => {
+                    let mut world = world.borrow_mut();
+                    let size = canvas_size(canvas.clone());
+                    if let Some(bounds) = universe.topology().bounds().or(universe.bounds()) {
+                        world.viewport.fit(bounds, size);
+                    }
+                    show_universe(&share, &edges, &universe);
+                    world.load(universe, generation);
+                    world.ink = share.ink();
+                    redraw(&world);
+                    report(None);
+                }
+                Some(Err(err)) => report(Some(format!("Cannot load {name}: {err}"))),
+                None => report(Some(format!("Cannot read {name}"))),
+            }
+        }
+    };
+
+    let error = {
+        let name = Rc::clone(&name);
+        move |_: Event| report(Some(format!("Cannot read {}", name.borrow())))
+    };
+
+    let target = canvas.as_ref();
+    Ok(vec![
+        Listener::new(target, "dragover", over)?,
+        Listener::new(target, "drop", drop)?,
+        Listener::new(reader.as_ref(), "load", load)?,
+        Listener::new(reader.as_ref(), "error", error)?,
+    ])
+}
+
+/// Zooms to show the whole pattern when the button is clicked.
+fn fit_listener(
+    button: &Element,
+    world: &Rc<RefCell<World>>,
+    easel: &Rc<RefCell<Easel>>,
+    dashboard: &Rc<Dashboard>,
+) -> Result<Listener> {
+    let (world, canvas) = (Rc::clone(world), easel.borrow().canvas());
+    let redraw = redrawer(easel, dashboard);
+    Ok(Listener::new(button, "click", move |_: Event| {
+        let mut world = world.borrow_mut();
+        let size = canvas_size(canvas.clone());
+        if let Some(bounds) = world.universe.bounds() {
+            world.viewport.fit(bounds, size);
+            redraw(&world);
+        }
+    })?)
+}
+
+/// Replaces the universe with a new soup when the button is clicked, and puts
+/// the soup's seed in the page URL so that the soup can be shared.
+fn soup_listener(
+    system: &System,
+    controls: Rc<SoupControls>,
+    world: &Rc<RefCell<World>>,
+    easel: &Rc<RefCell<Easel>>,
+    dashboard: &Rc<Dashboard>,
+) -> Result<Listener> {
+    let (system, world) = (system.clone(), Rc::clone(world));
+    let canvas = easel.borrow().canvas();
+    let redraw = redrawer(easel, dashboard);
+    let button = controls.button.clone();
+    Ok(Listener::new(&button, "click", move |_: Event| {
+        let soup = controls.soup();
+        let mut world = world.borrow_mut();
+        world.reset(soup.generate());
+        let bounds = world.universe.topology().bounds().unwrap_or(soup.bounds());
+        world.viewport.fit(bounds, canvas_size(canvas.clone()));
+        if let Ok(history) = system.window.history() {
+            _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&soup.to_query()));
+        }
+        redraw(&world);
+    })?)
+}
+
+/// Listens for changes to the edges of the universe, for window resizes, and
+/// for clicks on the undo button.
+fn edge_listeners(
+    system: &System,
+    controls: &Rc<EdgeControls>,
+    world: &Rc<RefCell<World>>,
+    easel: &Rc<RefCell<Easel>>,
+    dashboard: &Rc<Dashboard>,
+) -> Result<Vec<Listener>> {
+    let canvas = easel.borrow().canvas();
+    let redraw = redrawer(easel, dashboard);
+
+    let change = {
+        let (system, controls, world) = (system.clone(), Rc::clone(controls), Rc::clone(world));
+        let (canvas, redraw) = (canvas.clone(), redraw.clone());
+        move |_: Event| {
+            let mut world = world.borrow_mut();
+            let size = canvas_size(canvas.clone());
+            match (controls.edges(), world.universe.topology()) {
+                (Edges::Unbounded, _) => world.universe.set_topology(Topology::Plane),
+                (_, Topology::Plane) => {
+                    // Wrap around the visible cells, which move to the origin.
+                    let visible = world.viewport.visible(size);
+                    world.universe.translate(Point {
+                        i: -visible.min.i,
+                        j: -visible.min.j,
+                    });
+                    retopologize(&mut world, Topology::Torus(visible.size()), now(&system));
+                    if let Some(bounds) = world.universe.topology().bounds() {
+                        world.viewport.center_on(bounds, size);
+                    }
+                }
+                (_, Topology::Torus(_)) => (),
+            }
+            controls.show_undo(world.undo.is_some());
+            redraw(&world);
+        }
+    };
+
+    let resize = {
+        let (system, controls, world) = (system.clone(), Rc::clone(controls), Rc::clone(world));
+        let (easel, canvas, redraw) = (Rc::clone(easel), canvas.clone(), redraw.clone());
+        move |_: Event| {
+            let old = canvas_size(canvas.clone());
+            let new = fit_canvas(&system);
+            if new == old {
+                return;
+            }
+            easel.borrow_mut().resize_canvas(new);
+            let mut world = world.borrow_mut();
+            resize_world(&mut world, controls.edges(), old, new, now(&system));
+            controls.show_undo(world.undo.is_some());
+            redraw(&world);
+        }
+    };
+
+    let undo = {
+        let (controls, world) = (Rc::clone(controls), Rc::clone(world));
+        move |_: Event| {
+            let mut world = world.borrow_mut();
+            let Some(snapshot) = world.undo.take() else {
+                return;
+            };
+            world.universe = snapshot.universe;
+            world.generation = snapshot.generation;
+            world.stats = Stats::new();
+            if let Some(bounds) = world.universe.topology().bounds() {
+                world
+                    .viewport
+                    .center_on(bounds, canvas_size(canvas.clone()));
+            } else {
+                controls.set_edges(Edges::Unbounded);
+            }
+            controls.show_undo(false);
+            redraw(&world);
+        }
+    };
+
+    Ok(vec![
+        Listener::new(controls.select(), "change", change)?,
+        Listener::new(system.window.as_ref(), "resize", resize)?,
+        Listener::new(&controls.undo, "click", undo)?,
+    ])
+}
+
+/// Switches render modes when a new one is selected. Activity is only tracked
+/// while needed, so it starts afresh.
+fn mode_listener(
+    select: &HtmlSelectElement,
+    world: &Rc<RefCell<World>>,
+    easel: &Rc<RefCell<Easel>>,
+    dashboard: &Rc<Dashboard>,
+) -> Result<Listener> {
+    let (target, select, world) = (select.clone(), select.clone(), Rc::clone(world));
+    let redraw = redrawer(easel, dashboard);
+    Ok(Listener::new(&target, "change", move |_: Event| {
+        let mut world = world.borrow_mut();
+        world.mode = usize::try_from(select.selected_index())
+            .ok()
+            .and_then(|index| RenderMode::ALL.get(index).copied())
+            .unwrap_or_default();
+        world.activity = Activity::new();
+        redraw(&world);
+    })?)
+}
+
+/// Listens for the choice of a catalog entry, and for requests to place it or
+/// to stamp it.
+fn catalog_listeners(
+    controls: &Rc<CatalogControls>,
+    palette: &HtmlSelectElement,
+    world: &Rc<RefCell<World>>,
+    easel: &Rc<RefCell<Easel>>,
+    dashboard: &Rc<Dashboard>,
+) -> Result<Vec<Listener>> {
+    let canvas = easel.borrow().canvas();
+    let redraw = redrawer(easel, dashboard);
+
+    let change = {
+        let controls = Rc::clone(controls);
+        move |_: Event| controls.describe()
+    };
+
+    let place = {
+        let (controls, world, redraw) = (Rc::clone(controls), Rc::clone(world), redraw.clone());
+        move |_: Event| {
+            let mut world = world.borrow_mut();
+            let pattern = controls.entry().pattern();
+            let topology = world.universe.topology();
+            let center = topology.bounds().map(|bounds| bounds.center());
+            let at = pattern.corner_for(center.unwrap_or_default());
+            world.universe.stamp(&pattern, at);
+            world.stats.interrupt();
+            let visible = pattern.bounds_at(at);
+            world
+                .viewport
+                .center_on(visible, canvas_size(canvas.clone()));
+            redraw(&world);
+        }
+    };
+
+    let stamp = {
+        let (controls, palette, world) = (Rc::clone(controls), palette.clone(), Rc::clone(world));
+        move |_: Event| {
+            world.borrow_mut().stamp = controls.entry().pattern();
+            let index = Tool::ALL.iter().position(|&tool| tool == Tool::Stamp);
+            palette.set_selected_index(index.and_then(|i| i32::try_from(i).ok()).unwrap_or(-1));
+        }
+    };
+
+    // Classifying is slow, so the table of known objects is built only once
+    // it's wanted.
+    let take_census = {
+        let (controls, world) = (Rc::clone(controls), Rc::clone(world));
+        let mut classifier = None;
+        move |_: Event| {
+            let mut world = world.borrow_mut();
+            let classifier = classifier.get_or_insert_with(Classifier::new);
+            let islands = classifier.classify(&world.universe);
+            if let Err(err) = controls.show_census(world.generation, &census::census(&islands)) {
+                console::warn_1(&format!("Cannot show census: {err:?}").into());
+            }
+            world.census = Some(Census {
+                islands,
+                revision: world.universe.revision(),
+                generation: world.generation,
+            });
+            redraw(&world);
+        }
+    };
+
+    Ok(vec![
+        Listener::new(controls.select(), "change", change)?,
+        Listener::new(&controls.place, "click", place)?,
+        Listener::new(&controls.stamp, "click", stamp)?,
+        Listener::new(&controls.census, "click", take_census)?,
+    ])
+}
+
+/// Listens for changes to the simulation speed, and for single steps, which
+/// also extend the timeline.
+fn speed_listeners(
+    controls: &Rc<SpeedControls>,
+    history: &Rc<HistoryControls>,
+    world: &Rc<RefCell<World>>,
+    easel: &Rc<RefCell<Easel>>,
+    dashboard: &Rc<Dashboard>,
+) -> Result<Vec<Listener>> {
+    let change = {
+        let (controls, world) = (Rc::clone(controls), Rc::clone(world));
+        move |_: Event| {
+            let mut world = world.borrow_mut();
+            world.speed = controls.speed();
+            world.pace = Pace::new();
+        }
+    };
+
+    let step = {
+        let (history, world) = (Rc::clone(history), Rc::clone(world));
+        let redraw = redrawer(easel, dashboard);
+        move |_: Event| {
+            let mut world = world.borrow_mut();
+            world.step();
+            history.show(world.history.range(), world.generation);
+            redraw(&world);
+        }
+    };
+
+    Ok(vec![
+        Listener::new(controls.select(), "change", change)?,
+        Listener::new(&controls.step, "click", step)?,
+    ])
+}
+
+/// Listens for scrubbing of the timeline, for clicks on the back button, and
+/// for changes to the memory limit.
+fn history_listeners(
+    controls: &Rc<HistoryControls>,
+    world: &Rc<RefCell<World>>,
+    easel: &Rc<RefCell<Easel>>,
+    dashboard: &Rc<Dashboard>,
+) -> Result<Vec<Listener>> {
+    let redraw = redrawer(easel, dashboard);
+
+    let scrub = {
+        let (controls, world, redraw) = (Rc::clone(controls), Rc::clone(world), redraw.clone());
+        move |_: Event| {
+            let mut world = world.borrow_mut();
+            world.rewind(controls.generation());
+            redraw(&world);
+        }
+    };
+
+    let back = {
+        let (controls, world) = (Rc::clone(controls), Rc::clone(world));
+        move |_: Event| {
+            let mut world = world.borrow_mut();
+            let generation = world.generation.saturating_sub(1);
+            world.rewind(generation);
+            controls.show(world.history.range(), world.generation);
+            redraw(&world);
+        }
+    };
+
+    let limit = {
+        let (controls, world) = (Rc::clone(controls), Rc::clone(world));
+        move |_: Event| world.borrow_mut().history.set_limit(controls.limit())
+    };
+
+    Ok(vec![
+        Listener::new(controls.timeline(), "input", scrub)?,
+        Listener::new(&controls.back, "click", back)?,
+        Listener::new(controls.limit_select(), "change", limit)?,
+    ])
+}
+
+/// Listens for changes to the rule, and for clicks on the share button, which
+/// puts the universe in the URL fragment and copies the URL to the clipboard.
+fn share_listeners(
+    system: &System,
+    controls: &Rc<ShareControls>,
+    world: &Rc<RefCell<World>>,
+) -> Result<Vec<Listener>> {
+    let change = {
+        let (controls, world) = (Rc::clone(controls), Rc::clone(world));
+        move |_: Event| {
+            let mut world = world.borrow_mut();
+            match controls.rule() {
+                Ok(rule) => {
+                    world.universe.set_rule(rule);
+                    world.stats.interrupt();
+                }
+                Err(err) => console::warn_1(&err.to_string().into()),
+            }
+            controls.show_rule(world.universe.rule());
+            world.ink = controls.ink();
+        }
+    };
+
+    let ink = {
+        let (controls, world) = (Rc::clone(controls), Rc::clone(world));
+        move |_: Event| world.borrow_mut().ink = controls.ink()
+    };
+
+    let share = {
+        let (window, world) = (system.window.clone(), Rc::clone(world));
+        move |_: Event| {
+            let world = world.borrow();
+            let fragment = to_fragment(&world.universe, world.generation);
+            let location = window.location();
+            let path = location.pathname().unwrap_or_default();
+            if let Ok(history) = window.history() {
+                let url = format!("{path}#{fragment}");
+                _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&url));
+            }
+            if let Ok(href) = location.href() {
+                // The URL is shown either way, so a failure to copy is harmless.
+                _ = window.navigator().clipboard().write_text(&href);
+            }
+        }
+    };
+
+    Ok(vec![
+        Listener::new(controls.rule_input(), "change", change)?,
+        Listener::new(controls.ink_select(), "change", ink)?,
+        Listener::new(&controls.button, "click", share)?,
+    ])
+}
+
+/// Returns the universe to start with, and its generation, and fits the
+/// viewport to it. A shared universe in the URL fragment takes precedence over
+/// a soup in the query, and either over random cells.
+fn initial_universe(system: &System, canvas: SizeU32, viewport: &mut Viewport) -> (Universe, u32) {
+    let location = system.window.location();
+    let hash = location.hash().unwrap_or_default();
+    if let Some(fragment) = hash.strip_prefix('#').filter(|f| !f.is_empty()) {
+        match from_fragment(fragment) {
+            Ok((universe, generation)) => {
+                let bounds = universe.topology().bounds().or(universe.bounds());
+                if let Some(bounds) = bounds {
+                    viewport.fit(bounds, canvas);
+                }
+                return (universe, generation);
+            }
+            Err(err) => console::warn_1(&format!("Cannot load shared link: {err}").into()),
+        }
+    }
+    let mut universe = Universe::new();
+    let query = location.search().unwrap_or_default();
+    if let Some(soup) = Soup::from_query(&query) {
+        universe = soup.generate();
+        viewport.fit(soup.bounds(), canvas);
+    } else {
+        // Let there be light.
+        universe.speckle(viewport.visible(canvas).size());
+    }
+    (universe, 0)
+}
+
+pub struct App {
+    root: Element,
+    _easel: Rc<RefCell<Easel>>,
+    _listeners: Vec<Listener>,
+}
+
+impl App {
+    /// Returns the plane, unless the URL query sets up a one-dimensional
+    /// automaton instead.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Err`] if DOM interaction fails.
+    pub fn new(system: &System) -> Result<Self> {
+        let query = system.window.location().search().unwrap_or_default();
+        let Some(setup) = Setup::from_query(&query) else {
+            return App::plane(system);
+        };
+        let (root, easel, listeners) = spacetime::new(system, setup)?;
+        Ok(App {
+            root,
+            _easel: easel,
+            _listeners: listeners,
+        })
+    }
+
+    /// Returns the Game of Life, or another rule in the plane.
+    fn plane(system: &System) -> Result<Self> {
+        let canvas = fit_canvas(system);
+        let mut viewport = Viewport::new(INITIAL_ZOOM);
+        let (universe, generation) = initial_universe(system, canvas, &mut viewport);
+        let world = Rc::new(RefCell::new(World::new(universe, viewport, generation)));
+        let dashboard = Rc::new(Dashboard::new(system)?);
+        let history_controls = Rc::new(HistoryControls::new(system)?);
+
+        let render = renderer(system, &world, &dashboard, &history_controls);
+        let mut easel = Easel::start(system, render)?;
+        easel.resize_canvas(canvas);
+
+        let palette = new_palette(system)?;
+        let fit = BUTTON
+            .class("life-fit")
+            .attr("title", "Zoom to fit the pattern")
+            .text("Fit")
+            .to_element(system)?;
+        let modes = new_render_modes(system)?;
+        let controls = DIV.class("life-controls").child3(
+            palette.unchecked_ref::<Element>(),
+            &fit,
+            modes.unchecked_ref::<Element>(),
+        );
+        let soup_controls = Rc::new(SoupControls::new(system)?);
+        let edge_controls = Rc::new(EdgeControls::new(system)?);
+        let speed_controls = Rc::new(SpeedControls::new(system)?);
+        let catalog_controls = Rc::new(CatalogControls::new(system)?);
+        let share_controls = Rc::new(ShareControls::new(system)?);
+        show_universe(&share_controls, &edge_controls, &world.borrow().universe);
+        let banner = new_banner(system)?;
+        let root = DIV
+            .class("life")
+            .child3(
+                DIV.child2(&banner, easel.as_ref()),
+                DIV.child3(
+                    DIV.class("life-controls").child3(
+                        &speed_controls.root,
+                        &history_controls.root,
+                        controls,
+                    ),
+                    &catalog_controls.root,
+                    DIV.class("life-controls").child3(
+                        &edge_controls.root,
+                        &soup_controls.root,
+                        DIV.class("life-controls").child2(
+                            &share_controls.root,
+                            A.attr("href", "?elementary=30")
+                                .attr("title", "One-dimensional automata")
+                                .text("1D"),
+                        ),
+                    ),
+                ),
+                dashboard.root(),
+            )
+            .to_element(system)?;
+
+        let easel = Rc::new(RefCell::new(easel));
+        let mut listeners =
+            catalog_listeners(&catalog_controls, &palette, &world, &easel, &dashboard)?;
+        listeners.extend(canvas_listeners(&world, &easel, &dashboard, palette)?);
+        listeners.push(fit_listener(&fit, &world, &easel, &dashboard)?);
+        listeners.push(mode_listener(&modes, &world, &easel, &dashboard)?);
+        listeners.extend(speed_listeners(
+            &speed_controls,
+            &history_controls,
+            &world,
+            &easel,
+            &dashboard,
+        )?);
+        listeners.extend(edge_listeners(
+            system,
+            &edge_controls,
+            &world,
+            &easel,
+            &dashboard,
+        )?);
+        listeners.extend(history_listeners(
+            &history_controls,
+            &world,
+            &easel,
+            &dashboard,
+        )?);
+        listeners.extend(share_listeners(system, &share_controls, &world)?);
+        listeners.extend(drop_listeners(
+            &world,
+            &easel,
+            &dashboard,
+            &share_controls,
+            &edge_controls,
+            &banner,
+        )?);
+        listeners.push(soup_listener(
+            system,
+            soup_controls,
+            &world,
+            &easel,
+            &dashboard,
+        )?);
+        Ok(App {
+            root,
+            _easel: easel,
+            _listeners: listeners,
+        })
+    }
+}
+
+impl AsRef<Element> for App {
+    fn as_ref(&self) -> &Element {
+        &self.root
+    }
+}
@@ -0,0 +1,78 @@
+//! The URL- and filename-safe [base64] alphabet, without padding.
+//!
+//! [base64]: https://datatracker.ietf.org/doc/html/rfc4648#section-5
+
+use system::u32_to_usize;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+pub fn encode(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let mut group = [0; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let bits = u32::from(group[0]) << 16 | u32::from(group[1]) << 8 | u32::from(group[2]);
+        // Each byte of input needs at least one more character of output.
+        for k in 0..=chunk.len() {
+            let index = bits >> (18 - 6 * k) & 0x3f;
+            text.push(char::from(ALPHABET[u32_to_usize(index)]));
+        }
+    }
+    text
+}
+
+fn decode_char(c: u8) -> Option<u32> {
+    let index = ALPHABET.iter().position(|&a| a == c)?;
+    u32::try_from(index).ok()
+}
+
+/// Returns [`None`] if the text holds characters outside the alphabet, other
+/// than trailing padding, or if its length is impossible.
+pub fn decode(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim_end_matches('=').as_bytes();
+    let mut bytes = Vec::with_capacity(text.len() / 4 * 3 + 2);
+    for chunk in text.chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let bits = chunk.iter().enumerate().try_fold(0, |bits, (k, &c)| {
+            Some(bits | decode_char(c)? << (18 - 6 * k))
+        })?;
+        // The first of the four big-endian bytes is always zero.
+        bytes.extend_from_slice(&bits.to_be_bytes()[1..chunk.len()]);
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_values() {
+        // From RFC 4648, section 10, but without padding.
+        let cases = [
+            ("", ""),
+            ("f", "Zg"),
+            ("fo", "Zm8"),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg"),
+            ("fooba", "Zm9vYmE"),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (plain, encoded) in cases {
+            assert_eq!(encode(plain.as_bytes()), encoded);
+            assert_eq!(decode(encoded).as_deref(), Some(plain.as_bytes()));
+        }
+        assert_eq!(encode(&[0xfb, 0xff]), "-_8");
+        assert_eq!(decode("Zm9vYg=="), Some(b"foob".to_vec()));
+        assert_eq!(decode("Zm9vY"), None);
+        assert_eq!(decode("Zm9v+"), None);
+    }
+
+    #[test]
+    fn round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        assert_eq!(decode(&encode(&bytes)), Some(bytes));
+    }
+}
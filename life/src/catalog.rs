@@ -0,0 +1,286 @@
+//! Well-known patterns, in RLE.
+
+use std::fmt;
+
+use crate::pattern::Pattern;
+use crate::rle;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Category {
+    StillLife,
+    Oscillator,
+    Spaceship,
+    Gun,
+    /// Small patterns that take a long time to stabilize.
+    Methuselah,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Category::StillLife => "Still lifes",
+            Category::Oscillator => "Oscillators",
+            Category::Spaceship => "Spaceships",
+            Category::Gun => "Guns",
+            Category::Methuselah => "Methuselahs",
+        })
+    }
+}
+
+pub struct Entry {
+    pub name: &'static str,
+    pub category: Category,
+    pub description: &'static str,
+    rle: &'static str,
+}
+
+impl Entry {
+    pub fn pattern(&self) -> Pattern {
+        // The catalog is tested, so every entry parses.
+        rle::parse(self.rle)
+            .map(|rle| rle.pattern)
+            .unwrap_or_default()
+    }
+}
+
+/// Every entry, grouped by category.
+pub const CATALOG: [Entry; 27] = [
+    Entry {
+        name: "Block",
+        category: Category::StillLife,
+        description: "The smallest and most common still life.",
+        rle: "2o$2o!",
+    },
+    Entry {
+        name: "Beehive",
+        category: Category::StillLife,
+        description: "The second most common still life.",
+        rle: "b2o$o2bo$b2o!",
+    },
+    Entry {
+        name: "Loaf",
+        category: Category::StillLife,
+        description: "A seven-cell still life, common in the ash of soups.",
+        rle: "b2o$o2bo$bobo$2bo!",
+    },
+    Entry {
+        name: "Boat",
+        category: Category::StillLife,
+        description: "The only five-cell still life.",
+        rle: "2o$obo$bo!",
+    },
+    Entry {
+        name: "Tub",
+        category: Category::StillLife,
+        description: "Four cells around an empty center.",
+        rle: "bo$obo$bo!",
+    },
+    Entry {
+        name: "Ship",
+        category: Category::StillLife,
+        description: "A boat with a second stern.",
+        rle: "2o$obo$b2o!",
+    },
+    Entry {
+        name: "Pond",
+        category: Category::StillLife,
+        description: "Eight cells around a square hole.",
+        rle: "b2o$o2bo$o2bo$b2o!",
+    },
+    Entry {
+        name: "Long boat",
+        category: Category::StillLife,
+        description: "A boat stretched diagonally by one cell.",
+        rle: "2o$obo$bobo$2bo!",
+    },
+    Entry {
+        name: "Barge",
+        category: Category::StillLife,
+        description: "A tub stretched diagonally by one cell.",
+        rle: "bo$obo$bobo$2bo!",
+    },
+    Entry {
+        name: "Mango",
+        category: Category::StillLife,
+        description: "A beehive bent diagonally.",
+        rle: "b2o$o2bo$bo2bo$2b2o!",
+    },
+    Entry {
+        name: "Aircraft carrier",
+        category: Category::StillLife,
+        description: "Two hooks, tail to tail.",
+        rle: "2o$o2bo$2b2o!",
+    },
+    Entry {
+        name: "Eater 1",
+        category: Category::StillLife,
+        description: "Also called the fishhook, it destroys gliders that hit it, and survives.",
+        rle: "2o$obo$2bo$2b2o!",
+    },
+    Entry {
+        name: "Snake",
+        category: Category::StillLife,
+        description: "Six cells in an S, lying on its side.",
+        rle: "2obo$ob2o!",
+    },
+    Entry {
+        name: "Blinker",
+        category: Category::Oscillator,
+        description: "The smallest oscillator, alternating between a row and a column.",
+        rle: "3o!",
+    },
+    Entry {
+        name: "Toad",
+        category: Category::Oscillator,
+        description: "A period 2 oscillator of two offset rows.",
+        rle: "b3o$3o!",
+    },
+    Entry {
+        name: "Beacon",
+        category: Category::Oscillator,
+        description: "Two blocks whose inner corners blink, with period 2.",
+        rle: "2o$2o$2b2o$2b2o!",
+    },
+    Entry {
+        name: "Pulsar",
+        category: Category::Oscillator,
+        description: "The most common period 3 oscillator, with four-fold symmetry.",
+        rle: "x = 13, y = 13\n2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$\
+              2b3o3b3o$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+    },
+    Entry {
+        name: "Pentadecathlon",
+        category: Category::Oscillator,
+        description: "A period 15 oscillator, which evolves from a row of ten cells.",
+        rle: "x = 10, y = 3\n2bo4bo$2ob4ob2o$2bo4bo!",
+    },
+    Entry {
+        name: "Glider",
+        category: Category::Spaceship,
+        description: "The smallest spaceship, moving diagonally one cell every four generations.",
+        rle: "bo$2bo$3o!",
+    },
+    Entry {
+        name: "Lightweight spaceship",
+        category: Category::Spaceship,
+        description: "The smallest orthogonal spaceship, moving at half the speed of light.",
+        rle: "bo2bo$o$o3bo$4o!",
+    },
+    Entry {
+        name: "Middleweight spaceship",
+        category: Category::Spaceship,
+        description: "A longer cousin of the lightweight spaceship, at the same speed.",
+        rle: "3bo$bo3bo$o$o4bo$5o!",
+    },
+    Entry {
+        name: "Heavyweight spaceship",
+        category: Category::Spaceship,
+        description: "The longest of the three standard orthogonal spaceships.",
+        rle: "3b2o$bo4bo$o$o5bo$6o!",
+    },
+    Entry {
+        name: "Gosper glider gun",
+        category: Category::Gun,
+        description: "The first known gun, emitting a glider every 30 generations.",
+        rle: "x = 36, y = 9\n24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$\
+              2o8bo5bo3b2o$2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!",
+    },
+    Entry {
+        name: "R-pentomino",
+        category: Category::Methuselah,
+        description: "Five cells that take 1103 generations to stabilize.",
+        rle: "b2o$2o$bo!",
+    },
+    Entry {
+        name: "Acorn",
+        category: Category::Methuselah,
+        description: "Seven cells that take 5206 generations to stabilize.",
+        rle: "bo$3bo$2o2b3o!",
+    },
+    Entry {
+        name: "Diehard",
+        category: Category::Methuselah,
+        description: "Seven cells that vanish completely after 130 generations.",
+        rle: "6bo$2o$bo3b3o!",
+    },
+    Entry {
+        name: "Pi-heptomino",
+        category: Category::Methuselah,
+        description: "A common seven-cell pattern that takes 173 generations to stabilize.",
+        rle: "3o$obo$obo!",
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::{Settled, Stats};
+    use crate::universe::{Point, Universe};
+
+    fn universe(name: &str) -> Universe {
+        let entry = CATALOG.iter().find(|entry| entry.name == name).unwrap();
+        let mut universe = Universe::new();
+        universe.stamp(&entry.pattern(), Point::default());
+        universe
+    }
+
+    fn settled(name: &str, ticks: usize) -> Option<Settled> {
+        let mut universe = universe(name);
+        let mut stats = Stats::new();
+        for _ in 0..ticks {
+            let turnover = universe.tick();
+            stats.record(&universe, turnover);
+        }
+        stats.settled()
+    }
+
+    #[test]
+    fn entries_parse_and_are_grouped() {
+        for entry in &CATALOG {
+            assert!(rle::parse(entry.rle).is_ok(), "{}", entry.name);
+        }
+        assert!(CATALOG.is_sorted_by_key(|entry| entry.category));
+    }
+
+    #[test]
+    fn entries_behave_as_described() {
+        let oscillator = |period| Some(Settled::Oscillator { period });
+        let spaceship = |i, j| {
+            Some(Settled::Spaceship {
+                period: 4,
+                offset: Point { i, j },
+            })
+        };
+        let still_lifes = CATALOG
+            .iter()
+            .filter(|entry| entry.category == Category::StillLife);
+        for name in still_lifes.map(|entry| entry.name) {
+            assert_eq!(settled(name, 2), Some(Settled::StillLife), "{name}");
+        }
+        assert_eq!(settled("Toad", 3), oscillator(2));
+        assert_eq!(settled("Beacon", 3), oscillator(2));
+        assert_eq!(settled("Pulsar", 4), oscillator(3));
+        assert_eq!(settled("Pentadecathlon", 16), oscillator(15));
+        assert_eq!(settled("Glider", 5), spaceship(1, 1));
+        assert_eq!(settled("Lightweight spaceship", 5), spaceship(0, -2));
+        assert_eq!(settled("Middleweight spaceship", 5), spaceship(0, -2));
+        assert_eq!(settled("Heavyweight spaceship", 5), spaceship(0, -2));
+    }
+
+    #[test]
+    fn gun_fires_and_diehard_dies() {
+        let mut gun = universe("Gosper glider gun");
+        assert_eq!(gun.population(), 36);
+        for _ in 0..30 {
+            gun.tick();
+        }
+        assert_eq!(gun.population(), 36 + 5);
+        let mut diehard = universe("Diehard");
+        for _ in 0..129 {
+            diehard.tick();
+        }
+        assert_ne!(diehard.population(), 0);
+        diehard.tick();
+        assert_eq!(diehard.population(), 0);
+    }
+}
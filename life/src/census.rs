@@ -0,0 +1,267 @@
+//! Recognizing the objects in a universe: islands of connected cells are
+//! compared, in every rotation and reflection, with each phase of the still
+//! lifes, oscillators, and spaceships in the catalog.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::catalog::{CATALOG, Category, Entry};
+use crate::rule::Rule;
+use crate::universe::{Point, Universe};
+
+/// The longest period of any catalog object, which bounds the search for its
+/// phases.
+const MAX_PERIOD: u32 = 30;
+
+/// A shape, as sorted cell positions relative to the top left corner of its
+/// bounds.
+type Shape = Vec<(i32, i32)>;
+
+/// Returns the shape of the cells, moved so that their bounds start at the
+/// origin.
+fn shape(cells: impl IntoIterator<Item = (i32, i32)>) -> Shape {
+    let mut shape: Shape = cells.into_iter().collect();
+    let min_i = shape.iter().map(|&(i, _)| i).min().unwrap_or_default();
+    let min_j = shape.iter().map(|&(_, j)| j).min().unwrap_or_default();
+    for (i, j) in &mut shape {
+        (*i, *j) = (*i - min_i, *j - min_j);
+    }
+    shape.sort_unstable();
+    shape
+}
+
+/// A rotation or reflection of a cell's row and column.
+type Transform = fn(i32, i32) -> (i32, i32);
+
+/// Returns the same shape for the cells in any rotation or reflection: the
+/// least of the eight.
+fn canonical(cells: &[Point]) -> Shape {
+    let transforms: [Transform; 8] = [
+        |i, j| (i, j),
+        |i, j| (j, -i),
+        |i, j| (-i, -j),
+        |i, j| (-j, i),
+        |i, j| (i, -j),
+        |i, j| (-j, -i),
+        |i, j| (-i, j),
+        |i, j| (j, i),
+    ];
+    transforms
+        .iter()
+        .map(|transform| shape(cells.iter().map(|p| transform(p.i, p.j))))
+        .min()
+        .unwrap_or_default()
+}
+
+/// A group of cells, each within one cell of another, or pieces of a known
+/// object that are a little farther apart.
+pub struct Island {
+    pub cells: Vec<Point>,
+    /// The catalog entry the island matches, if any.
+    pub object: Option<&'static Entry>,
+}
+
+impl Island {
+    /// Returns the name of the object, or describes the unknown island.
+    pub fn name(&self) -> String {
+        self.object.map_or_else(
+            || format!("Unknown ({} cells)", self.cells.len()),
+            |entry| entry.name.to_owned(),
+        )
+    }
+}
+
+/// Splits cells into groups, each reachable from any of its cells by steps of
+/// at most `reach` rows and columns to other cells in the group.
+fn groups(cells: &[Point], reach: i32) -> Vec<Vec<Point>> {
+    let mut unvisited: HashSet<Point> = cells.iter().copied().collect();
+    let mut groups = Vec::new();
+    for &start in cells {
+        if !unvisited.remove(&start) {
+            continue;
+        }
+        let (mut group, mut next) = (Vec::new(), vec![start]);
+        while let Some(p) = next.pop() {
+            group.push(p);
+            for di in -reach..=reach {
+                for dj in -reach..=reach {
+                    let q = Point {
+                        i: p.i + di,
+                        j: p.j + dj,
+                    };
+                    if unvisited.remove(&q) {
+                        next.push(q);
+                    }
+                }
+            }
+        }
+        groups.push(group);
+    }
+    groups
+}
+
+/// Recognizes the still lifes, oscillators, and spaceships of the catalog,
+/// under Conway's rule.
+pub struct Classifier {
+    /// The catalog entry with each shape, as any phase in any orientation.
+    known: HashMap<Shape, &'static Entry>,
+}
+
+impl Classifier {
+    pub fn new() -> Classifier {
+        let mut known = HashMap::new();
+        let objects = CATALOG.iter().filter(|entry| {
+            matches!(
+                entry.category,
+                Category::StillLife | Category::Oscillator | Category::Spaceship
+            )
+        });
+        for entry in objects {
+            let mut universe = Universe::new();
+            universe.stamp(&entry.pattern(), Point::default());
+            let first = shape(universe.cells().map(|p| (p.i, p.j)));
+            // Run until the first phase recurs, wherever it has moved.
+            for _ in 0..MAX_PERIOD {
+                let cells: Vec<Point> = universe.cells().collect();
+                known.insert(canonical(&cells), entry);
+                universe.tick();
+                if shape(universe.cells().map(|p| (p.i, p.j))) == first {
+                    break;
+                }
+            }
+        }
+        Classifier { known }
+    }
+
+    fn recognize(&self, cells: &[Point]) -> Option<&'static Entry> {
+        self.known.get(&canonical(cells)).copied()
+    }
+
+    /// Groups the cells with others up to the first reach away, recognizing
+    /// each group, or else splitting it by the remaining reaches.
+    fn split(&self, cells: &[Point], reaches: &[i32], islands: &mut Vec<Island>) {
+        let Some((&reach, rest)) = reaches.split_first() else {
+            return;
+        };
+        for cells in groups(cells, reach) {
+            if let Some(entry) = self.recognize(&cells) {
+                islands.push(Island {
+                    cells,
+                    object: Some(entry),
+                });
+            } else if rest.is_empty() {
+                islands.push(Island {
+                    cells,
+                    object: None,
+                });
+            } else {
+                self.split(&cells, rest, islands);
+            }
+        }
+    }
+
+    /// Splits the universe into islands, and recognizes each. Some objects
+    /// have phases in pieces, like the pulsar, whose pieces are two cells
+    /// apart, or the pentadecathlon, whose are as far as seven; so cells are
+    /// grouped with others that far away, and groups that aren't recognized
+    /// are split, until they're islands of touching cells. Under other rules
+    /// than Conway's, nothing is recognized.
+    pub fn classify(&self, universe: &Universe) -> Vec<Island> {
+        let cells: Vec<Point> = universe.cells().collect();
+        let mut islands = Vec::new();
+        if universe.rule() == Rule::CONWAY {
+            self.split(&cells, &[7, 2, 1], &mut islands);
+        } else {
+            islands.extend(groups(&cells, 1).into_iter().map(|cells| Island {
+                cells,
+                object: None,
+            }));
+        }
+        islands
+    }
+}
+
+/// Returns the name of each object among the islands, with the number of
+/// islands having it, most common first. Unknown islands are counted by size.
+pub fn census(islands: &[Island]) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for island in islands {
+        *counts.entry(island.name()).or_default() += 1;
+    }
+    let mut census: Vec<_> = counts.into_iter().collect();
+    census.sort_unstable_by(|(a, m), (b, n)| n.cmp(m).then_with(|| a.cmp(b)));
+    census
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stamp(universe: &mut Universe, name: &str, at: Point) {
+        let entry = CATALOG.iter().find(|entry| entry.name == name).unwrap();
+        universe.stamp(&entry.pattern(), at);
+    }
+
+    #[test]
+    fn shapes_are_the_same_in_any_orientation() {
+        let glider = [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+        let form = canonical(&glider.map(|(i, j)| Point { i, j }));
+        let turned = glider.map(|(i, j)| Point { i: j, j: -i });
+        let flipped = glider.map(|(i, j)| Point {
+            i: 10 - i,
+            j: j + 3,
+        });
+        assert_eq!(canonical(&turned), form);
+        assert_eq!(canonical(&flipped), form);
+        let r_pentomino = [(0, 1), (0, 2), (1, 0), (1, 1), (2, 1)];
+        assert_ne!(canonical(&r_pentomino.map(|(i, j)| Point { i, j })), form);
+    }
+
+    #[test]
+    fn every_phase_of_every_object_is_recognized() {
+        let classifier = Classifier::new();
+        for entry in CATALOG
+            .iter()
+            .filter(|entry| entry.category < Category::Gun)
+        {
+            let mut universe = Universe::new();
+            universe.stamp(&entry.pattern(), Point::default());
+            for generation in 0..MAX_PERIOD {
+                let islands = classifier.classify(&universe);
+                let names: Vec<_> = islands
+                    .iter()
+                    .map(|island| island.object.map(|entry| entry.name))
+                    .collect();
+                assert_eq!(names, [Some(entry.name)], "generation {generation}");
+                universe.tick();
+            }
+        }
+    }
+
+    #[test]
+    fn census_counts_objects() {
+        let mut universe = Universe::new();
+        stamp(&mut universe, "Block", Point { i: 0, j: 0 });
+        stamp(&mut universe, "Block", Point { i: 0, j: 10 });
+        stamp(&mut universe, "Beehive", Point { i: 10, j: 0 });
+        stamp(&mut universe, "Blinker", Point { i: 10, j: 10 });
+        stamp(&mut universe, "Glider", Point { i: 20, j: 0 });
+        stamp(&mut universe, "Pulsar", Point { i: 20, j: 10 });
+        stamp(&mut universe, "R-pentomino", Point { i: 40, j: 0 });
+        let islands = Classifier::new().classify(&universe);
+        assert_eq!(
+            census(&islands),
+            [
+                ("Block".to_owned(), 2),
+                ("Beehive".to_owned(), 1),
+                ("Blinker".to_owned(), 1),
+                ("Glider".to_owned(), 1),
+                ("Pulsar".to_owned(), 1),
+                ("Unknown (5 cells)".to_owned(), 1),
+            ]
+        );
+        // Other rules have other objects.
+        universe.set_rule("B36/S23".parse().unwrap());
+        let islands = Classifier::new().classify(&universe);
+        assert!(islands.iter().all(|island| island.object.is_none()));
+    }
+}
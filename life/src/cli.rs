@@ -0,0 +1,366 @@
+//! A native command-line runner, which runs a pattern file for a number of
+//! generations, then prints the result as RLE, with statistics in comments;
+//! or which takes a census of the objects left by random soups.
+
+use std::fmt::{self, Write};
+use std::io::{self, Read};
+use std::process::ExitCode;
+
+use crate::format;
+use crate::macrocell;
+use crate::rule::Rule;
+use crate::search::{self, Search};
+use crate::share::{self, to_rle};
+use crate::soup::{MAX_SIZE, Soup};
+use crate::stats::Stats;
+use crate::universe::{Topology, Universe};
+
+pub const USAGE: &str = "\
+Usage: life [-n GENERATIONS] [-r RULE] [-t TOPOLOGY] [-m] FILE
+       life --census SOUPS [-n GENERATIONS] [-s SEED] [-z SIZE] [-d DENSITY]
+            [-y SYMMETRY]
+
+Runs the pattern in FILE, which is RLE, plaintext, or a macrocell, as its
+name (.rle, .cells, or .mc) or else its content shows, and prints the result
+as RLE. If FILE is -, reads standard input.
+
+With --census, runs SOUPS random soups under Conway's rule, with consecutive
+seeds, until each settles, then prints how many of each object they left,
+flagging rare and unknown objects.
+
+Options:
+  -n GENERATIONS  How many generations to run (default: 0), or with --census,
+                  the most each soup may run (default: 10000)
+  -r RULE         The rule, such as B36/S23, instead of the pattern's own
+  -t TOPOLOGY     plane, or T and the width and height of a torus, such as
+                  T64,48, instead of the pattern's own
+  -m              Prints the result as a macrocell, rather than RLE
+
+Census options:
+  -s SEED         The seed of the first soup (default: 0)
+  -z SIZE         The side of each soup, in cells (default: 16)
+  -d DENSITY      The percentage of live cells in each soup (default: 50)
+  -y SYMMETRY     C1, C2, C4, or D8 (default: C1)";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The arguments did not match the usage.
+    Usage(String),
+    /// The pattern file could not be read.
+    Read(String),
+    Pattern(share::Error),
+    Macrocell(macrocell::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Usage(message) => write!(f, "{message}\n\n{USAGE}"),
+            Error::Read(message) => write!(f, "cannot read pattern: {message}"),
+            Error::Pattern(e) => e.fmt(f),
+            Error::Macrocell(e) => e.fmt(f),
+        }
+    }
+}
+
+impl From<share::Error> for Error {
+    fn from(value: share::Error) -> Self {
+        Error::Pattern(value)
+    }
+}
+
+impl From<format::Error> for Error {
+    fn from(value: format::Error) -> Self {
+        match value {
+            format::Error::Pattern(e) => Error::Pattern(e),
+            format::Error::Macrocell(e) => Error::Macrocell(e),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Options {
+    path: String,
+    generations: Option<u32>,
+    rule: Option<Rule>,
+    topology: Option<Topology>,
+    /// Whether to print the result as a macrocell.
+    macrocell: bool,
+    /// How many soups to take a census of, instead of running a pattern.
+    census: Option<u32>,
+    /// The first soup of the census.
+    soup: Soup,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            path: String::new(),
+            generations: None,
+            rule: None,
+            topology: None,
+            macrocell: false,
+            census: None,
+            soup: Soup {
+                size: 16,
+                ..Soup::default()
+            },
+        }
+    }
+}
+
+/// Parses a numeric option value, naming the option if it's bad.
+fn number<T: std::str::FromStr>(arg: &str, value: &str) -> Result<T, Error> {
+    value
+        .parse()
+        .map_err(|_| Error::Usage(format!("bad value for {arg}: {value}")))
+}
+
+impl Options {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Options, Error> {
+        let mut options = Options::default();
+        let mut path = None;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| Error::Usage(format!("{arg} needs a value")))
+            };
+            match arg.as_str() {
+                "-n" => {
+                    let value = value()?;
+                    options.generations = Some(
+                        value
+                            .parse()
+                            .map_err(|_| Error::Usage(format!("bad generation count: {value}")))?,
+                    );
+                }
+                "-m" => options.macrocell = true,
+                "--census" => options.census = Some(number(&arg, &value()?)?),
+                "-s" => options.soup.seed = number(&arg, &value()?)?,
+                "-z" => options.soup.size = number::<u32>(&arg, &value()?)?.clamp(1, MAX_SIZE),
+                "-d" => options.soup.density = number::<u8>(&arg, &value()?)?.min(100),
+                "-y" => {
+                    let value = value()?;
+                    options.soup.symmetry = value
+                        .parse()
+                        .map_err(|()| Error::Usage(format!("bad symmetry: {value}")))?;
+                }
+                "-r" => options.rule = Some(value()?.parse().map_err(share::Error::Rule)?),
+                "-t" => {
+                    let value = value()?;
+                    let topology = if value == "plane" { "" } else { &value };
+                    let topology = topology
+                        .parse()
+                        .map_err(|()| share::Error::Topology(value.clone()))?;
+                    options.topology = Some(topology);
+                }
+                "-h" | "--help" => return Err(Error::Usage("Runs Life patterns.".to_owned())),
+                _ if arg.starts_with('-') && arg != "-" => {
+                    return Err(Error::Usage(format!("unknown option: {arg}")));
+                }
+                _ if path.is_none() => path = Some(arg),
+                _ => return Err(Error::Usage(format!("unexpected argument: {arg}"))),
+            }
+        }
+        if options.census.is_some() {
+            if let Some(path) = path {
+                return Err(Error::Usage(format!("unexpected argument: {path}")));
+            }
+            if options.rule.is_some() || options.topology.is_some() {
+                return Err(Error::Usage(
+                    "a census runs Conway's rule on a plane".to_owned(),
+                ));
+            }
+            return Ok(options);
+        }
+        options.path = path.ok_or_else(|| Error::Usage("no pattern file".to_owned()))?;
+        Ok(options)
+    }
+}
+
+/// Returns the universe described by the text of a pattern file, and its
+/// generation, with any rule and topology from the options.
+fn load(text: &str, options: &Options) -> Result<(Universe, u32), Error> {
+    let (mut universe, generation) = format::load(&options.path, text)?;
+    if let Some(rule) = options.rule {
+        universe.set_rule(rule);
+    }
+    if let Some(topology) = options.topology {
+        universe.set_topology(topology);
+    }
+    Ok((universe, generation))
+}
+
+/// Runs the universe for the number of generations in the options, and returns
+/// the result as RLE or a macrocell, with statistics as comments.
+fn run(mut universe: Universe, generation: u32, options: &Options) -> String {
+    let generations = options.generations.unwrap_or_default();
+    let mut stats = Stats::new();
+    let (mut births, mut deaths) = (0, 0);
+    let (mut min, mut max) = (universe.population(), universe.population());
+    for _ in 0..generations {
+        let turnover = universe.tick();
+        stats.record(&universe, turnover);
+        births += turnover.births;
+        deaths += turnover.deaths;
+        min = min.min(universe.population());
+        max = max.max(universe.population());
+    }
+    let generation = generation.saturating_add(generations);
+    let settled = stats
+        .settled()
+        .map_or_else(|| "no".to_owned(), |settled| settled.to_string());
+    let mut report = String::new();
+    // Writing to a string can't fail.
+    _ = writeln!(report, "#C Generation: {generation}");
+    _ = writeln!(
+        report,
+        "#C Population: {} (min {min}, max {max})",
+        universe.population()
+    );
+    _ = writeln!(report, "#C Births: {births}, deaths: {deaths}");
+    _ = writeln!(report, "#C Settled: {settled}");
+    let rle = to_rle(&universe, generation);
+    if options.macrocell {
+        return macrocell::write(&rle, &report);
+    }
+    _ = write!(report, "{rle}");
+    report
+}
+
+/// Takes a census of the specified number of soups, and returns the report.
+fn census(soups: u32, options: &Options) -> String {
+    let limit = options.generations.unwrap_or(search::DEFAULT_LIMIT);
+    let mut search = Search::new(options.soup, limit);
+    for _ in 0..soups {
+        search.run_one();
+    }
+    search.report()
+}
+
+fn read(path: &str) -> Result<String, Error> {
+    let mut text = String::new();
+    let result = if path == "-" {
+        io::stdin().read_to_string(&mut text).map(|_| ())
+    } else {
+        std::fs::read_to_string(path).map(|file| text = file)
+    };
+    result.map_err(|e| Error::Read(format!("{path}: {e}")))?;
+    Ok(text)
+}
+
+/// Runs the command line, printing the result or an error.
+#[must_use]
+pub fn main() -> ExitCode {
+    let result = Options::parse(std::env::args().skip(1)).and_then(|options| {
+        if let Some(soups) = options.census {
+            return Ok(census(soups, &options));
+        }
+        let (universe, generation) = load(&read(&options.path)?, &options)?;
+        Ok(run(universe, generation, &options))
+    });
+    match result {
+        Ok(report) => {
+            print!("{report}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("life: {e}");
+            ExitCode::from(if matches!(e, Error::Usage(_)) { 2 } else { 1 })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(args: &str) -> Result<Options, Error> {
+        Options::parse(args.split_whitespace().map(str::to_owned))
+    }
+
+    #[test]
+    fn parses_options() {
+        assert_eq!(
+            options("-n 30 -r B36/S23 -t T20,10 glider.rle"),
+            Ok(Options {
+                path: "glider.rle".to_owned(),
+                generations: Some(30),
+                rule: "B36/S23".parse().ok(),
+                topology: "T20,10".parse().ok(),
+                ..Options::default()
+            })
+        );
+        let census = options("--census 100 -s 7 -z 20 -d 40 -y c4").unwrap();
+        assert_eq!(census.census, Some(100));
+        assert_eq!(
+            census.soup,
+            Soup {
+                seed: 7,
+                density: 40,
+                size: 20,
+                symmetry: "C4".parse().unwrap(),
+            }
+        );
+        assert!(matches!(options("--census 1 a.rle"), Err(Error::Usage(_))));
+        assert!(matches!(
+            options("--census 1 -r B36/S23"),
+            Err(Error::Usage(_))
+        ));
+        assert!(matches!(options("--census x"), Err(Error::Usage(_))));
+        assert_eq!(
+            options("-t plane -").map(|options| options.topology),
+            Ok(Some(Topology::Plane))
+        );
+        assert!(matches!(options(""), Err(Error::Usage(_))));
+        assert!(matches!(options("-n"), Err(Error::Usage(_))));
+        assert!(matches!(options("-n x a.rle"), Err(Error::Usage(_))));
+        assert!(matches!(options("-x a.rle"), Err(Error::Usage(_))));
+        assert!(matches!(options("a.rle b.rle"), Err(Error::Usage(_))));
+        assert!(matches!(
+            options("-t K9 a.rle"),
+            Err(Error::Pattern(share::Error::Topology(_)))
+        ));
+    }
+
+    #[test]
+    fn runs_gliders_on_a_torus() {
+        let options = options("-n 80 -t T8,8 glider.cells").unwrap();
+        let (universe, generation) = load(".O.\n..O\nOOO\n", &options).unwrap();
+        let report = run(universe, generation, &options);
+        // The glider moves one cell diagonally every four generations, so it
+        // wraps around twice, then moves halfway across again.
+        assert_eq!(
+            report,
+            "#C Generation: 80\n\
+             #C Population: 5 (min 5, max 5)\n\
+             #C Births: 160, deaths: 160\n\
+             #C Settled: spaceship of period 4, moving (1, 1)\n\
+             #CXRLE Pos=4,4 Gen=80\n\
+             x = 3, y = 3, rule = B3/S23:T8,8\n\
+             bo$2bo$3o!\n"
+        );
+    }
+
+    #[test]
+    fn resumes_from_the_pattern_generation() {
+        let text = "#CXRLE Pos=-1,-1 Gen=7\nx = 3, y = 1, rule = B3/S23\n3o!";
+        let options = options("-n 1 blinker.rle").unwrap();
+        let (universe, generation) = load(text, &options).unwrap();
+        let report = run(universe, generation, &options);
+        assert!(report.contains("#C Settled: no\n"));
+        assert!(report.ends_with("#CXRLE Pos=0,-2 Gen=8\nx = 1, y = 3, rule = B3/S23\no$o$o!\n"));
+    }
+
+    #[test]
+    fn reads_and_writes_macrocells() {
+        let text = "[M2] (golly 4.0)\n#R B3/S23\n$$$$...***$\n";
+        let options = options("-n 1 -m blinker.mc").unwrap();
+        let (universe, generation) = load(text, &options).unwrap();
+        assert_eq!(universe.population(), 3);
+        let report = run(universe, generation, &options);
+        assert!(report.starts_with("[M2] (life)\n#C Generation: 1\n"));
+        assert!(report.ends_with("#R B3/S23\n#G 1\n$$$....*$....*$....*$\n"));
+    }
+}
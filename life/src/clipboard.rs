@@ -0,0 +1,89 @@
+//! Moving patterns between the universe and the clipboard, as text other Life
+//! programs understand.
+
+use crate::format;
+use crate::pattern::Pattern;
+use crate::rle::{self, Rle};
+use crate::rule::Rule;
+use crate::share::{self, check_states};
+use crate::universe::{Bounds, Universe};
+
+/// Returns the cells of the universe within the bounds as RLE, with the rule.
+pub fn copy(universe: &Universe, bounds: Bounds) -> String {
+    let (pattern, _) = Pattern::enclosing(universe.states_within(bounds));
+    let rle = Rle {
+        pattern,
+        rule: Some(universe.rule().to_string()),
+        ..Rle::default()
+    };
+    rle.to_string()
+}
+
+/// Parses text from the clipboard as a pattern, which may be RLE, or
+/// plaintext if it holds nothing but comments, `.`, `O`, and `*`.
+///
+/// # Errors
+///
+/// Will return [`Err`] if the text is neither plaintext nor valid RLE, or if
+/// cells are in states the specified rule doesn't have.
+pub fn paste(text: &str, rule: Rule) -> Result<Pattern, share::Error> {
+    if format::is_plaintext(text) {
+        return Ok(Pattern::from_plaintext(text));
+    }
+    let pattern = rle::parse(text)?.pattern;
+    check_states(&pattern, rule)?;
+    Ok(pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::universe::{Cell, Point};
+
+    fn cells(pattern: &Pattern) -> Vec<(i32, i32)> {
+        pattern.states().map(|(p, _)| (p.i, p.j)).collect()
+    }
+
+    #[test]
+    fn copies_what_it_pastes() {
+        let mut universe = Universe::new();
+        let glider = paste("bo$2bo$3o!", Rule::CONWAY).unwrap();
+        universe.stamp(&glider, Point { i: 10, j: -20 });
+        universe.set(Point { i: 50, j: 50 }, Cell::LIVE);
+        let bounds = Bounds {
+            min: Point { i: 0, j: -30 },
+            max: Point { i: 20, j: 0 },
+        };
+        let text = copy(&universe, bounds);
+        assert_eq!(text, "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n");
+        assert_eq!(paste(&text, Rule::CONWAY).unwrap(), glider);
+        let plaintext = paste("!Name: Glider\n.O\n..O\nOOO\n", Rule::CONWAY).unwrap();
+        assert_eq!(cells(&plaintext), cells(&glider));
+        assert!(paste("#N Odd\n3?", Rule::CONWAY).is_err());
+        assert_eq!(
+            paste("ACA!", Rule::CONWAY).err(),
+            Some(share::Error::State(3))
+        );
+        assert!(paste("ACA!", Rule::Wireworld).is_ok());
+    }
+
+    #[test]
+    fn rotates_and_flips() {
+        let glider = paste("bo$2bo$3o!", Rule::CONWAY).unwrap();
+        // A quarter turn clockwise sends the glider down and to the left.
+        assert_eq!(
+            cells(&glider.rotated()),
+            cells(&paste("o$obo$2o!", Rule::CONWAY).unwrap())
+        );
+        assert_eq!(
+            cells(&glider.flipped()),
+            cells(&paste("bo$o$3o!", Rule::CONWAY).unwrap())
+        );
+        let r = paste("b2o$2o$bo!", Rule::CONWAY).unwrap();
+        let turned = (0..4).fold(r.clone(), |r, _| r.rotated());
+        assert_eq!(turned, r);
+        assert_eq!(r.flipped().flipped(), r);
+        let wide = paste("3o!", Rule::CONWAY).unwrap().rotated();
+        assert_eq!((wide.size().width, wide.size().height), (1, 3));
+    }
+}
@@ -0,0 +1,624 @@
+//! Form controls beside the canvas.
+
+use std::ops::RangeInclusive;
+
+use web_sys::{Element, HtmlInputElement, HtmlSelectElement, js_sys};
+
+use easel::Result;
+use sugar::prelude::*;
+use system::{System, f64_to_u32_saturating};
+
+use crate::catalog::{CATALOG, Entry};
+use crate::edit::Tool;
+use crate::elementary::{Setup, Start};
+use crate::history;
+use crate::rule::{self, MAX_STATES, Rule};
+use crate::shade::RenderMode;
+use crate::soup::{MAX_SIZE, Soup, Symmetry};
+use crate::speed::Speed;
+use crate::universe::Cell;
+
+pub fn new_palette(system: &System) -> Result<HtmlSelectElement> {
+    let palette = SELECT
+        .class("life-palette")
+        .attr(
+            "title",
+            "Drawing tool (hold shift to erase; select, then copy and paste as RLE; R and F turn and flip a pasted pattern)",
+        )
+        .to_element(system)?;
+    for tool in Tool::ALL {
+        let option = OPTION.to_element(system)?;
+        option.set_text_content(Some(tool.name()));
+        palette.append_with_node_1(&option)?;
+    }
+    Ok(palette.dyn_cast()?)
+}
+
+pub fn new_render_modes(system: &System) -> Result<HtmlSelectElement> {
+    let modes = SELECT
+        .class("life-modes")
+        .attr("title", "How cells are colored")
+        .to_element(system)?;
+    for mode in RenderMode::ALL {
+        let option = OPTION.to_element(system)?;
+        option.set_text_content(Some(&mode.to_string()));
+        modes.append_with_node_1(&option)?;
+    }
+    Ok(modes.dyn_cast()?)
+}
+
+/// A message about a pattern file that could not be loaded, hidden until there
+/// is one.
+pub fn new_banner(system: &System) -> Result<Element> {
+    let banner = P
+        .class("life-banner")
+        .attr("role", "alert")
+        .attr("hidden", "")
+        .to_element(system)?;
+    Ok(banner)
+}
+
+/// A browsable list of well-known patterns, with buttons to place the chosen
+/// pattern or to stamp it with the pointer, and to find the patterns in the
+/// universe.
+pub struct CatalogControls {
+    pub root: Element,
+    system: System,
+    select: HtmlSelectElement,
+    description: Element,
+    pub place: Element,
+    pub stamp: Element,
+    pub census: Element,
+    census_list: Element,
+}
+
+impl CatalogControls {
+    pub fn new(system: &System) -> Result<Self> {
+        let select = SELECT
+            .class("life-catalog")
+            .attr("title", "Pattern catalog")
+            .to_element(system)?;
+        for (index, entry) in CATALOG.iter().enumerate() {
+            if index == 0 || CATALOG[index - 1].category != entry.category {
+                let group = OPTGROUP.to_element(system)?;
+                group.set_attribute("label", &entry.category.to_string())?;
+                select.append_with_node_1(&group)?;
+            }
+            let option = OPTION.to_element(system)?;
+            option.set_text_content(Some(entry.name));
+            if let Some(group) = select.last_element_child() {
+                group.append_with_node_1(&option)?;
+            }
+        }
+        let description = SPAN.class("life-catalog__description").to_element(system)?;
+        description.set_text_content(Some(CATALOG[0].description));
+        let place = BUTTON
+            .class("life-catalog__place")
+            .attr("title", "Put the pattern at the center of the universe")
+            .text("Place")
+            .to_element(system)?;
+        let stamp = BUTTON
+            .class("life-catalog__stamp")
+            .attr("title", "Click the canvas to stamp the pattern")
+            .text("Stamp")
+            .to_element(system)?;
+        let census = BUTTON
+            .class("life-catalog__census")
+            .attr("title", "Outline and count the patterns in the universe")
+            .text("Census")
+            .to_element(system)?;
+        let census_list = UL.class("life-census").to_element(system)?;
+        let root = DIV
+            .class("life-controls")
+            .child3(
+                &select,
+                DIV.child3(&place, &stamp, &census),
+                DIV.child2(&description, &census_list),
+            )
+            .to_element(system)?;
+        Ok(CatalogControls {
+            root,
+            system: system.clone(),
+            select: select.dyn_cast()?,
+            description,
+            place,
+            stamp,
+            census,
+            census_list,
+        })
+    }
+
+    pub fn select(&self) -> &HtmlSelectElement {
+        &self.select
+    }
+
+    pub fn entry(&self) -> &'static Entry {
+        usize::try_from(self.select.selected_index())
+            .ok()
+            .and_then(|index| CATALOG.get(index))
+            .unwrap_or(&CATALOG[0])
+    }
+
+    /// Shows the description of the selected entry.
+    pub fn describe(&self) {
+        let description = self.entry().description;
+        self.description.set_text_content(Some(description));
+    }
+
+    /// Lists the number of each object found at the specified generation.
+    pub fn show_census(&self, generation: u32, census: &[(String, usize)]) -> Result<()> {
+        let heading = format!("Census @ {generation}");
+        self.census_list.set_text_content(None);
+        for line in std::iter::once(heading).chain(
+            census
+                .iter()
+                .map(|(label, count)| format!("{count} × {label}")),
+        ) {
+            let item = LI.to_element(&self.system)?;
+            item.set_text_content(Some(&line));
+            self.census_list.append_with_node_1(&item)?;
+        }
+        Ok(())
+    }
+}
+
+/// Inputs for the simulation speed, and for stepping one generation at a time.
+pub struct SpeedControls {
+    pub root: Element,
+    select: HtmlSelectElement,
+    pub step: Element,
+}
+
+impl SpeedControls {
+    pub fn new(system: &System) -> Result<Self> {
+        let select = SELECT
+            .class("life-speed")
+            .attr("title", "Generations per second")
+            .to_element(system)?;
+        for speed in Speed::ALL {
+            let option = OPTION.to_element(system)?;
+            option.set_text_content(Some(&speed.to_string()));
+            if speed == Speed::DEFAULT {
+                option.set_attribute("selected", "")?;
+            }
+            select.append_with_node_1(&option)?;
+        }
+        let step = BUTTON
+            .class("life-speed__step")
+            .attr("title", "Advance one generation")
+            .text("Step")
+            .to_element(system)?;
+        let root = DIV
+            .class("life-controls")
+            .child2(&select, &step)
+            .to_element(system)?;
+        Ok(SpeedControls {
+            root,
+            select: select.dyn_cast()?,
+            step,
+        })
+    }
+
+    pub fn select(&self) -> &HtmlSelectElement {
+        &self.select
+    }
+
+    pub fn speed(&self) -> Speed {
+        usize::try_from(self.select.selected_index())
+            .ok()
+            .and_then(|index| Speed::ALL.get(index).copied())
+            .unwrap_or(Speed::DEFAULT)
+    }
+}
+
+/// What happens at the edges of the universe, and to the universe when the
+/// canvas is resized.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Edges {
+    /// The universe is unbounded, so resizing the canvas only moves the view.
+    #[default]
+    Unbounded,
+    /// The universe is a torus resized along with the canvas, losing any live
+    /// cells that no longer fit.
+    Crop,
+    /// The universe is a torus that grows with the canvas, but never shrinks.
+    Enlarge,
+}
+
+impl Edges {
+    pub const ALL: [Edges; 3] = [Edges::Unbounded, Edges::Crop, Edges::Enlarge];
+
+    fn label(self) -> &'static str {
+        match self {
+            Edges::Unbounded => "Unbounded",
+            Edges::Crop => "Wrap, crop to fit",
+            Edges::Enlarge => "Wrap, enlarge only",
+        }
+    }
+}
+
+/// Inputs for choosing the edges of the universe, and for undoing resizes
+/// that lost live cells.
+pub struct EdgeControls {
+    pub root: Element,
+    select: HtmlSelectElement,
+    /// Hidden unless there is something to undo.
+    pub undo: Element,
+}
+
+impl EdgeControls {
+    pub fn new(system: &System) -> Result<Self> {
+        let select = SELECT
+            .class("life-edges")
+            .attr(
+                "title",
+                "What happens at the edges, and when the window is resized",
+            )
+            .to_element(system)?;
+        for edges in Edges::ALL {
+            let option = OPTION.to_element(system)?;
+            option.set_text_content(Some(edges.label()));
+            select.append_with_node_1(&option)?;
+        }
+        let undo = BUTTON
+            .class("life-edges__undo")
+            .attr("title", "Restore the cells lost when the window shrank")
+            .attr("hidden", "")
+            .text("Undo resize")
+            .to_element(system)?;
+        let root = DIV
+            .class("life-controls")
+            .child2(&select, &undo)
+            .to_element(system)?;
+        Ok(EdgeControls {
+            root,
+            select: select.dyn_cast()?,
+            undo,
+        })
+    }
+
+    pub fn select(&self) -> &HtmlSelectElement {
+        &self.select
+    }
+
+    pub fn edges(&self) -> Edges {
+        usize::try_from(self.select.selected_index())
+            .ok()
+            .and_then(|index| Edges::ALL.get(index).copied())
+            .unwrap_or_default()
+    }
+
+    pub fn set_edges(&self, edges: Edges) {
+        let index = Edges::ALL
+            .iter()
+            .position(|&e| e == edges)
+            .unwrap_or_default();
+        self.select
+            .set_selected_index(i32::try_from(index).unwrap_or_default());
+    }
+
+    pub fn show_undo(&self, show: bool) {
+        _ = self.undo.toggle_attribute_with_force("hidden", !show);
+    }
+}
+
+/// Inputs for generating random soups.
+pub struct SoupControls {
+    pub root: Element,
+    density: HtmlInputElement,
+    size: HtmlInputElement,
+    symmetry: HtmlSelectElement,
+    pub button: Element,
+}
+
+impl SoupControls {
+    pub fn new(system: &System) -> Result<Self> {
+        let density = INPUT
+            .class("life-soup__density")
+            .attr("type", "number")
+            .attr("min", "1")
+            .attr("max", "99")
+            .attr("value", "50")
+            .attr("title", "Soup density (%)")
+            .to_element(system)?;
+        let size = INPUT
+            .class("life-soup__size")
+            .attr("type", "number")
+            .attr("min", "1")
+            .attr("max", "1024")
+            .attr("value", "64")
+            .attr("title", "Soup size (cells)")
+            .to_element(system)?;
+        let symmetry = SELECT
+            .class("life-soup__symmetry")
+            .attr("title", "Soup symmetry")
+            .to_element(system)?;
+        for value in Symmetry::ALL {
+            let option = OPTION.to_element(system)?;
+            option.set_text_content(Some(&value.to_string()));
+            symmetry.append_with_node_1(&option)?;
+        }
+        let button = BUTTON
+            .class("life-soup__button")
+            .attr("title", "Fill the universe with a new random soup")
+            .text("New soup")
+            .to_element(system)?;
+        let root = DIV
+            .class("life-controls")
+            .child2(DIV.child3(&density, &size, &symmetry), &button)
+            .to_element(system)?;
+        Ok(SoupControls {
+            root,
+            density: density.dyn_cast()?,
+            size: size.dyn_cast()?,
+            symmetry: symmetry.dyn_cast()?,
+            button,
+        })
+    }
+
+    /// Returns a soup with a random seed, and other parameters as specified by
+    /// the inputs.
+    pub fn soup(&self) -> Soup {
+        let default = Soup::default();
+        let number =
+            |input: &HtmlInputElement| Some(input.value_as_number()).filter(|n| n.is_finite());
+        Soup {
+            seed: f64_to_u32_saturating(js_sys::Math::random() * f64::from(u32::MAX)),
+            density: number(&self.density).map_or(default.density, |n| {
+                u8::try_from(f64_to_u32_saturating(n).min(100)).unwrap_or_default()
+            }),
+            size: number(&self.size).map_or(default.size, |n| {
+                f64_to_u32_saturating(n).clamp(1, MAX_SIZE)
+            }),
+            symmetry: usize::try_from(self.symmetry.selected_index())
+                .ok()
+                .and_then(|index| Symmetry::ALL.get(index).copied())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// The rule, and a button that puts a link to the universe in the page URL.
+/// The rule, the state drawn by the pencil under rules with more than two,
+/// and a button to share the universe.
+pub struct ShareControls {
+    pub root: Element,
+    rule: HtmlInputElement,
+    ink: HtmlSelectElement,
+    pub button: Element,
+}
+
+impl ShareControls {
+    pub fn new(system: &System) -> Result<Self> {
+        let rule = INPUT
+            .class("life-share__rule")
+            .attr("type", "text")
+            .attr("size", "10")
+            .attr("spellcheck", "false")
+            .attr(
+                "title",
+                "Rule, such as B3/S23, B36/S23, B2/S/C3 (Brian's Brain), B2/S34H (hexagonal), R5,C0,M1,S34..58,B34..45,NM (Bosco), or WireWorld",
+            )
+            .to_element(system)?;
+        let ink = SELECT
+            .class("life-share__ink")
+            .attr("title", "State drawn by the pencil")
+            .attr("hidden", "")
+            .to_element(system)?;
+        // One option per state but dead, shown as the rule needs them.
+        for _ in 1..MAX_STATES {
+            let option = OPTION.to_element(system)?;
+            ink.append_with_node_1(&option)?;
+        }
+        let button = BUTTON
+            .class("life-share__button")
+            .attr("title", "Link to this pattern, and copy the link")
+            .text("Share")
+            .to_element(system)?;
+        let root = DIV
+            .class("life-controls")
+            .child3(&rule, &ink, &button)
+            .to_element(system)?;
+        Ok(ShareControls {
+            root,
+            rule: rule.dyn_cast()?,
+            ink: ink.dyn_cast()?,
+            button,
+        })
+    }
+
+    pub fn rule_input(&self) -> &HtmlInputElement {
+        &self.rule
+    }
+
+    /// # Errors
+    ///
+    /// Will return [`Err`] if the input does not hold a supported rule.
+    pub fn rule(&self) -> std::result::Result<Rule, rule::Error> {
+        self.rule.value().parse()
+    }
+
+    pub fn ink_select(&self) -> &HtmlSelectElement {
+        &self.ink
+    }
+
+    /// Returns the state chosen for the pencil.
+    pub fn ink(&self) -> Cell {
+        u8::try_from(self.ink.selected_index()).map_or(Cell::LIVE, |index| Cell(index + 1))
+    }
+
+    /// Shows the rule, and offers its states for the pencil. The choice of
+    /// state is kept if the rule has it.
+    pub fn show_rule(&self, rule: Rule) {
+        self.rule.set_value(&rule.to_string());
+        for index in 0..self.ink.length() {
+            let Some(option) = self.ink.item(index) else {
+                continue;
+            };
+            let state = u8::try_from(index + 1).unwrap_or(u8::MAX);
+            option.set_text_content(Some(&rule.state_name(Cell(state))));
+            _ = option.toggle_attribute_with_force("hidden", state >= rule.states());
+        }
+        if self.ink().0 >= rule.states() {
+            self.ink.set_selected_index(0);
+        }
+        _ = self
+            .ink
+            .toggle_attribute_with_force("hidden", rule.states() <= 2);
+    }
+}
+
+/// Choices of memory for past generations, in megabytes.
+const HISTORY_LIMITS_MB: [usize; 4] = [16, 64, 256, 1024];
+
+/// A timeline of past generations, a button to step back through them, and the
+/// memory they may use.
+pub struct HistoryControls {
+    pub root: Element,
+    timeline: HtmlInputElement,
+    pub back: Element,
+    limit: HtmlSelectElement,
+}
+
+impl HistoryControls {
+    pub fn new(system: &System) -> Result<Self> {
+        let timeline = INPUT
+            .class("life-history__timeline")
+            .attr("type", "range")
+            .attr("min", "0")
+            .attr("max", "0")
+            .attr("title", "Rewind to an earlier generation")
+            .to_element(system)?;
+        let back = BUTTON
+            .class("life-history__back")
+            .attr("title", "Go back one generation")
+            .text("Back")
+            .to_element(system)?;
+        let limit = SELECT
+            .class("life-history__limit")
+            .attr("title", "Memory for past generations")
+            .to_element(system)?;
+        for mb in HISTORY_LIMITS_MB {
+            let option = OPTION.to_element(system)?;
+            option.set_text_content(Some(&format!("{mb} MB")));
+            if mb << 20 == history::DEFAULT_LIMIT {
+                option.set_attribute("selected", "")?;
+            }
+            limit.append_with_node_1(&option)?;
+        }
+        let root = DIV
+            .class("life-controls")
+            .child3(&back, &timeline, &limit)
+            .to_element(system)?;
+        Ok(HistoryControls {
+            root,
+            timeline: timeline.dyn_cast()?,
+            back,
+            limit: limit.dyn_cast()?,
+        })
+    }
+
+    pub fn timeline(&self) -> &HtmlInputElement {
+        &self.timeline
+    }
+
+    pub fn limit_select(&self) -> &HtmlSelectElement {
+        &self.limit
+    }
+
+    /// Returns the generation chosen on the timeline.
+    pub fn generation(&self) -> u32 {
+        f64_to_u32_saturating(self.timeline.value_as_number())
+    }
+
+    /// Returns the chosen memory limit, in bytes.
+    pub fn limit(&self) -> usize {
+        usize::try_from(self.limit.selected_index())
+            .ok()
+            .and_then(|index| HISTORY_LIMITS_MB.get(index))
+            .map_or(history::DEFAULT_LIMIT, |mb| mb << 20)
+    }
+
+    /// Shows the range of recorded generations, and the current one.
+    pub fn show(&self, range: Option<RangeInclusive<u32>>, generation: u32) {
+        let range = range.unwrap_or(generation..=generation);
+        self.timeline.set_min(&range.start().to_string());
+        self.timeline.set_max(&range.end().to_string());
+        self.timeline.set_value_as_number(f64::from(generation));
+    }
+}
+
+/// The rule and start of a one-dimensional automaton, and a button to restart
+/// it.
+pub struct ElementaryControls {
+    pub root: Element,
+    rule: HtmlInputElement,
+    start: HtmlSelectElement,
+    pub restart: Element,
+}
+
+impl ElementaryControls {
+    pub fn new(system: &System, setup: Setup) -> Result<Self> {
+        let rule = INPUT
+            .class("life-elementary__rule")
+            .attr("type", "number")
+            .attr("min", "0")
+            .attr("max", "255")
+            .attr("title", "Wolfram rule number, such as 30, 90, or 110")
+            .to_element(system)?;
+        let start = SELECT
+            .class("life-elementary__start")
+            .attr("title", "First generation")
+            .child2(OPTION.text("Single cell"), OPTION.text("Random"))
+            .to_element(system)?;
+        let restart = BUTTON
+            .class("life-elementary__restart")
+            .attr("title", "Start again from the first generation")
+            .text("Restart")
+            .to_element(system)?;
+        // Plane Life is the page without a query.
+        let life = A.attr("href", "?").text("2D").to_element(system)?;
+        let root = DIV
+            .class("life-controls")
+            .child2(DIV.child3(&rule, &start, &restart), &life)
+            .to_element(system)?;
+        let controls = ElementaryControls {
+            root,
+            rule: rule.dyn_cast()?,
+            start: start.dyn_cast()?,
+            restart,
+        };
+        controls.rule.set_value(&setup.rule.to_string());
+        let random = matches!(setup.start, Start::Random(_));
+        controls.start.set_selected_index(i32::from(random));
+        Ok(controls)
+    }
+
+    pub fn rule_input(&self) -> &HtmlInputElement {
+        &self.rule
+    }
+
+    pub fn start_select(&self) -> &HtmlSelectElement {
+        &self.start
+    }
+
+    /// Returns the setup specified by the inputs, with a new seed for a random
+    /// start. Shows the rule actually used, if the input held another number.
+    pub fn setup(&self) -> Setup {
+        let default = Setup::default();
+        let rule = Some(self.rule.value_as_number())
+            .filter(|n| n.is_finite())
+            .map_or(default.rule, |n| {
+                u8::try_from(f64_to_u32_saturating(n)).unwrap_or(u8::MAX)
+            });
+        self.rule.set_value(&rule.to_string());
+        let start = if self.start.selected_index() == 1 {
+            Start::Random(f64_to_u32_saturating(
+                js_sys::Math::random() * f64::from(u32::MAX),
+            ))
+        } else {
+            Start::Single
+        };
+        Setup { rule, start }
+    }
+}
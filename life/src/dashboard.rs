@@ -0,0 +1,99 @@
+use wasm_bindgen::prelude::*;
+use web_sys::{CanvasRenderingContext2d, Element, HtmlCanvasElement};
+
+use easel::{Error, Result};
+use sugar::prelude::*;
+use system::{System, usize_to_u32};
+
+use crate::stats::{SPARKLINE_LEN, Stats};
+use crate::universe::Universe;
+
+const SPARKLINE_HEIGHT: u32 = 40;
+const SPARKLINE_COLOR: &str = "hsl(145, 19%, 45%)"; // Dark jade.
+
+/// Statistics shown beside the canvas.
+pub struct Dashboard {
+    root: Element,
+    sparkline: CanvasRenderingContext2d,
+    population: Element,
+    turnover: Element,
+    settled: Element,
+}
+
+impl Dashboard {
+    /// # Errors
+    ///
+    /// Will return [`Err`] if DOM interaction fails.
+    pub fn new(system: &System) -> Result<Self> {
+        let canvas = CANVAS
+            .class("life-sparkline")
+            .attr("title", "Population")
+            .to_element(system)?
+            .dyn_cast::<HtmlCanvasElement>()?;
+        canvas.set_width(usize_to_u32(SPARKLINE_LEN));
+        canvas.set_height(SPARKLINE_HEIGHT);
+        let sparkline = canvas
+            .get_context("2d")?
+            .ok_or(Error::Context2d)?
+            .dyn_cast::<CanvasRenderingContext2d>()?;
+
+        let population = SPAN.to_element(system)?;
+        let turnover = SPAN.to_element(system)?;
+        let settled = SPAN.to_element(system)?;
+        let root = DIV
+            .class("life-stats")
+            .child2(
+                canvas.unchecked_ref::<Element>(),
+                DIV.class("life-stats__text")
+                    .child3(&population, &turnover, &settled),
+            )
+            .to_element(system)?;
+
+        Ok(Dashboard {
+            root,
+            sparkline,
+            population,
+            turnover,
+            settled,
+        })
+    }
+
+    pub fn update(&self, universe: &Universe, stats: &Stats) {
+        let population = universe.population();
+        self.population
+            .set_text_content(Some(&format!("Population: {population}")));
+        let turnover = stats.turnover();
+        let (births, deaths) = (turnover.births, turnover.deaths);
+        self.turnover
+            .set_text_content(Some(&format!("Births: {births}, deaths: {deaths}")));
+        let settled = stats.settled().map_or_else(
+            || "Running".to_owned(),
+            |settled| format!("Settled: {settled}"),
+        );
+        self.settled.set_text_content(Some(&settled));
+        self.draw_sparkline(stats);
+    }
+
+    /// Plots recent populations, scaled to fill the height of the sparkline.
+    #[expect(clippy::cast_precision_loss)] // Populations are plotted roughly.
+    fn draw_sparkline(&self, stats: &Stats) {
+        let context = &self.sparkline;
+        let height = f64::from(SPARKLINE_HEIGHT);
+        context.clear_rect(0.0, 0.0, f64::from(usize_to_u32(SPARKLINE_LEN)), height);
+        let (min, max) = stats
+            .populations()
+            .fold((u64::MAX, 0), |(min, max), n| (min.min(n), max.max(n)));
+        let range = max.saturating_sub(min).max(1) as f64;
+        context.begin_path();
+        context.set_stroke_style_str(SPARKLINE_COLOR);
+        for (x, n) in (0..).zip(stats.populations()) {
+            let y = height - 1.0 - (n - min) as f64 / range * (height - 2.0);
+            context.line_to(f64::from(x) + 0.5, y);
+        }
+        context.stroke();
+    }
+
+    pub fn root(&self) -> &Element {
+        &self.root
+    }
+}
@@ -0,0 +1,171 @@
+//! Drawing on the universe with a pointer.
+
+use crate::pattern::Pattern;
+use crate::universe::{Bounds, Cell, Point};
+
+/// What a click on the canvas does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tool {
+    /// Toggles the clicked cell, then paints its new state along the drag.
+    Pencil,
+    /// Drags the viewport.
+    Pan,
+    /// Sets the live cells of the pattern chosen from the catalog, centered on
+    /// the clicked cell.
+    Stamp,
+    /// Drags out a rectangle of cells to copy.
+    Select,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 4] = [Tool::Pencil, Tool::Pan, Tool::Stamp, Tool::Select];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Tool::Pencil => "Pencil",
+            Tool::Pan => "Pan",
+            Tool::Stamp => "Stamp",
+            Tool::Select => "Select",
+        }
+    }
+}
+
+/// A drag in progress.
+pub enum Drag {
+    Paint {
+        /// The cell under the pointer as of the previous event.
+        last: Point,
+        /// The state to which cells under the pointer are set.
+        paint: Cell,
+    },
+    Pan {
+        /// The canvas pixel under the pointer as of the previous event.
+        last: (f64, f64),
+    },
+    Select {
+        /// The cell where the drag began, at one corner of the selection.
+        anchor: Point,
+    },
+}
+
+/// Returns the rectangle with the specified cells at opposite corners.
+pub fn rectangle(a: Point, b: Point) -> Bounds {
+    Bounds {
+        min: Point {
+            i: a.i.min(b.i),
+            j: a.j.min(b.j),
+        },
+        max: Point {
+            i: a.i.max(b.i),
+            j: a.j.max(b.j),
+        },
+    }
+}
+
+/// A pasted pattern, following the pointer until it's placed.
+pub struct Ghost {
+    pub pattern: Pattern,
+    /// The cell on which the pattern is centered.
+    pub center: Point,
+}
+
+impl Ghost {
+    /// Returns where the top left corner of the pattern would be placed.
+    pub fn corner(&self) -> Point {
+        self.pattern.corner_for(self.center)
+    }
+}
+
+/// The cells on the straight line between two points, inclusive, according to
+/// [Bresenham's algorithm](https://en.wikipedia.org/wiki/Bresenham%27s_line_algorithm).
+pub struct Line {
+    next: Option<Point>,
+    end: Point,
+    /// Absolute distance between the endpoints, and step direction, per axis.
+    di: i64,
+    dj: i64,
+    si: i32,
+    sj: i32,
+    /// Accumulated deviation from the ideal line.
+    err: i64,
+}
+
+impl Line {
+    pub fn new(start: Point, end: Point) -> Line {
+        let di = i64::from(start.i.abs_diff(end.i));
+        let dj = i64::from(start.j.abs_diff(end.j));
+        Line {
+            next: Some(start),
+            end,
+            di,
+            dj,
+            si: if start.i < end.i { 1 } else { -1 },
+            sj: if start.j < end.j { 1 } else { -1 },
+            err: dj - di,
+        }
+    }
+}
+
+impl Iterator for Line {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> {
+        let p = self.next?;
+        if p == self.end {
+            self.next = None;
+            return Some(p);
+        }
+        let mut q = p;
+        let e2 = 2 * self.err;
+        if e2 >= -self.di {
+            self.err -= self.di;
+            q.j += self.sj;
+        }
+        if e2 <= self.dj {
+            self.err += self.dj;
+            q.i += self.si;
+        }
+        self.next = Some(q);
+        Some(p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(start: (i32, i32), end: (i32, i32)) -> Vec<(i32, i32)> {
+        let start = Point {
+            i: start.0,
+            j: start.1,
+        };
+        let end = Point { i: end.0, j: end.1 };
+        Line::new(start, end).map(|p| (p.i, p.j)).collect()
+    }
+
+    #[test]
+    fn single_point() {
+        assert_eq!(line((3, 4), (3, 4)), [(3, 4)]);
+    }
+
+    #[test]
+    fn straight_and_diagonal() {
+        assert_eq!(line((0, 0), (0, 3)), [(0, 0), (0, 1), (0, 2), (0, 3)]);
+        assert_eq!(line((2, 0), (0, 0)), [(2, 0), (1, 0), (0, 0)]);
+        assert_eq!(line((0, 2), (2, 0)), [(0, 2), (1, 1), (2, 0)]);
+    }
+
+    #[test]
+    fn shallow_and_steep_lines_have_no_gaps() {
+        for (start, end) in [((0, 0), (2, 7)), ((7, 1), (0, 3)), ((5, 5), (-1, 0))] {
+            let points = line(start, end);
+            assert_eq!(points.first(), Some(&start));
+            assert_eq!(points.last(), Some(&end));
+            for pair in points.windows(2) {
+                let (a, b) = (pair[0], pair[1]);
+                assert!(a.0.abs_diff(b.0) <= 1 && a.1.abs_diff(b.1) <= 1);
+                assert_ne!(a, b);
+            }
+        }
+    }
+}
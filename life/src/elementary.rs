@@ -0,0 +1,227 @@
+//! One-dimensional elementary cellular automata, in which each cell's next
+//! state depends only on itself and its two neighbors. Rules are numbered as
+//! by Wolfram: bit `k` of the rule is the next state of a cell whose left
+//! neighbor, itself, and right neighbor spell `k` in binary.
+
+use std::fmt;
+use std::str::FromStr;
+
+use math::LinearCongruentialGenerator;
+
+/// The first generation of an automaton.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Start {
+    /// One live cell in the middle.
+    #[default]
+    Single,
+    /// Each cell live or dead at random, reproducibly from a seed.
+    Random(u32),
+}
+
+impl fmt::Display for Start {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Start::Single => write!(f, "single"),
+            Start::Random(seed) => write!(f, "{seed}"),
+        }
+    }
+}
+
+impl FromStr for Start {
+    type Err = ();
+
+    /// Parses `single`, or the seed of a random start.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("single") {
+            return Ok(Start::Single);
+        }
+        s.parse().map(Start::Random).map_err(|_| ())
+    }
+}
+
+/// Instructions for starting an automaton, which can be shared in a URL.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Setup {
+    pub rule: u8,
+    pub start: Start,
+}
+
+impl Default for Setup {
+    fn default() -> Self {
+        Setup {
+            rule: 30,
+            start: Start::Single,
+        }
+    }
+}
+
+impl Setup {
+    /// Parses a URL query string such as `?elementary=110&start=single`. Returns
+    /// [`None`] unless the query names a rule.
+    pub fn from_query(query: &str) -> Option<Setup> {
+        let mut start = Start::default();
+        let mut rule = None;
+        for pair in query.trim_start_matches('?').split('&') {
+            let Some((key, value)) = pair.split_once('=') else {
+                continue;
+            };
+            match key {
+                "elementary" => rule = value.parse().ok(),
+                "start" => start = value.parse().unwrap_or_default(),
+                _ => (),
+            }
+        }
+        Some(Setup { rule: rule?, start })
+    }
+
+    /// Returns a URL query string from which [`Self::from_query`] recreates
+    /// this setup.
+    pub fn to_query(self) -> String {
+        let Setup { rule, start } = self;
+        format!("?elementary={rule}&start={start}")
+    }
+}
+
+/// A row of cells, whose ends are neighbors.
+pub struct Elementary {
+    rule: u8,
+    cells: Vec<bool>,
+    generation: u32,
+}
+
+impl Elementary {
+    pub fn new(setup: Setup, width: usize) -> Elementary {
+        let width = width.max(1);
+        let cells = match setup.start {
+            Start::Single => (0..width).map(|j| j == width / 2).collect(),
+            Start::Random(seed) => {
+                let mut random = LinearCongruentialGenerator::from_seed(seed);
+                // The high bit, since the low bits of the LCG are far from
+                // random.
+                (0..width).map(|_| random.next_u32() >> 31 != 0).collect()
+            }
+        };
+        Elementary {
+            rule: setup.rule,
+            cells,
+            generation: 0,
+        }
+    }
+
+    pub fn rule(&self) -> u8 {
+        self.rule
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    pub fn cells(&self) -> &[bool] {
+        &self.cells
+    }
+
+    pub fn step(&mut self) {
+        let n = self.cells.len();
+        let at = |j: usize| u8::from(self.cells[j % n]);
+        let next = (0..n)
+            .map(|j| {
+                let k = at(j + n - 1) << 2 | at(j) << 1 | at(j + 1);
+                self.rule >> k & 1 != 0
+            })
+            .collect();
+        self.cells = next;
+        self.generation += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs the rule from a single cell, and returns each generation as text.
+    fn diagram(rule: u8, width: usize, generations: usize) -> Vec<String> {
+        let setup = Setup {
+            rule,
+            start: Start::Single,
+        };
+        let mut automaton = Elementary::new(setup, width);
+        let mut rows = Vec::new();
+        for _ in 0..=generations {
+            let row = automaton.cells().iter().map(|&c| if c { '#' } else { '.' });
+            rows.push(row.collect());
+            automaton.step();
+        }
+        rows
+    }
+
+    #[test]
+    fn known_rules_draw_known_diagrams() {
+        assert_eq!(
+            diagram(30, 11, 4),
+            [
+                ".....#.....",
+                "....###....",
+                "...##..#...",
+                "..##.####..",
+                ".##..#...#.",
+            ]
+        );
+        assert_eq!(
+            diagram(90, 11, 4),
+            [
+                ".....#.....",
+                "....#.#....",
+                "...#...#...",
+                "..#.#.#.#..",
+                ".#.......#.",
+            ]
+        );
+        assert_eq!(
+            diagram(110, 11, 5),
+            [
+                ".....#.....",
+                "....##.....",
+                "...###.....",
+                "..##.#.....",
+                ".#####.....",
+                "##...#.....",
+            ]
+        );
+        // Rules with bit 0 set fill an empty row.
+        let empty = Setup {
+            rule: 1,
+            start: Start::Single,
+        };
+        let mut automaton = Elementary::new(empty, 3);
+        automaton.step();
+        assert_eq!(automaton.cells(), [false, false, false]);
+        automaton.step();
+        assert_eq!(automaton.cells(), [true, true, true]);
+    }
+
+    #[test]
+    fn rule_30_center_column() {
+        // OEIS A051023.
+        let expected = "1101110011000101100100111010111";
+        let mut automaton = Elementary::new(Setup::default(), 101);
+        let mut column = String::new();
+        for _ in 0..expected.len() {
+            column.push(if automaton.cells()[50] { '1' } else { '0' });
+            automaton.step();
+        }
+        assert_eq!(column, expected);
+        assert_eq!(automaton.generation(), 31);
+    }
+
+    #[test]
+    fn setups_round_trip_through_queries() {
+        let setup = Setup {
+            rule: 110,
+            start: Start::Random(42),
+        };
+        assert_eq!(Setup::from_query(&setup.to_query()), Some(setup));
+        assert_eq!(Setup::from_query("?elementary=30"), Some(Setup::default()));
+        assert_eq!(Setup::from_query("?elementary=256"), None);
+        assert_eq!(Setup::from_query("?seed=1"), None);
+    }
+}
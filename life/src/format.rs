@@ -0,0 +1,131 @@
+//! Telling pattern files apart, whether they come from the command line or are
+//! dropped on the page: RLE, plaintext, or macrocells.
+
+use std::fmt;
+
+use crate::macrocell;
+use crate::pattern::Pattern;
+use crate::rle::{self, Rle};
+use crate::share::{self, from_rle};
+use crate::universe::Universe;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Rle,
+    /// Rows of `.` and `O`, after `!` comments, as in `.cells` files.
+    Plaintext,
+    Macrocell,
+}
+
+impl Format {
+    /// Works out the format of a file from its name and text. A macrocell is
+    /// known by its header, whatever its name; otherwise the extension decides,
+    /// or if there's none it knows, the text does.
+    pub fn detect(name: &str, text: &str) -> Format {
+        if text.starts_with(macrocell::MAGIC) {
+            return Format::Macrocell;
+        }
+        let extension = name.rsplit_once('.').map(|(_, extension)| extension);
+        let is = |known: &str| extension.is_some_and(|e| e.eq_ignore_ascii_case(known));
+        if is("mc") {
+            Format::Macrocell
+        } else if is("cells") {
+            Format::Plaintext
+        } else if is("rle") || !is_plaintext(text) {
+            Format::Rle
+        } else {
+            Format::Plaintext
+        }
+    }
+}
+
+/// Returns true if the text holds nothing but `!` comments, `.`, `O`, and `*`.
+pub fn is_plaintext(text: &str) -> bool {
+    text.lines()
+        .filter(|line| !line.starts_with('!'))
+        .all(|line| line.trim().chars().all(|c| matches!(c, '.' | 'O' | '*')))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    Pattern(share::Error),
+    Macrocell(macrocell::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Pattern(e) => e.fmt(f),
+            Error::Macrocell(e) => e.fmt(f),
+        }
+    }
+}
+
+impl From<share::Error> for Error {
+    fn from(value: share::Error) -> Self {
+        Error::Pattern(value)
+    }
+}
+
+impl From<rle::Error> for Error {
+    fn from(value: rle::Error) -> Self {
+        Error::Pattern(share::Error::Rle(value))
+    }
+}
+
+impl From<macrocell::Error> for Error {
+    fn from(value: macrocell::Error) -> Self {
+        Error::Macrocell(value)
+    }
+}
+
+/// Returns the universe described by a pattern file with the specified name,
+/// with the file's own rule and topology, if any, and its generation.
+///
+/// # Errors
+///
+/// Will return [`Err`] if the text is not a valid pattern in its format.
+pub fn load(name: &str, text: &str) -> Result<(Universe, u32), Error> {
+    let rle = match Format::detect(name, text) {
+        Format::Rle => rle::parse(text)?,
+        Format::Plaintext => Rle {
+            pattern: Pattern::from_plaintext(text),
+            ..Rle::default()
+        },
+        Format::Macrocell => macrocell::parse(text)?,
+    };
+    Ok(from_rle(rle)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_formats_by_name_and_content() {
+        let glider = ".O\n..O\nOOO\n";
+        assert_eq!(Format::detect("a.cells", glider), Format::Plaintext);
+        assert_eq!(Format::detect("glider", glider), Format::Plaintext);
+        assert_eq!(Format::detect("a.RLE", "bo$2bo$3o!"), Format::Rle);
+        assert_eq!(Format::detect("glider", "bo$2bo$3o!"), Format::Rle);
+        assert_eq!(Format::detect("a.mc", ""), Format::Macrocell);
+        assert_eq!(Format::detect("a.rle", "[M2] (golly)\n"), Format::Macrocell);
+    }
+
+    #[test]
+    fn loads_each_format_alike() {
+        let (rle, _) = load("a.rle", "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!").unwrap();
+        let (plaintext, _) = load("a.cells", "!Name: Glider\n.O\n..O\nOOO\n").unwrap();
+        assert_eq!(rle.sorted_cells(), plaintext.sorted_cells());
+        let written = macrocell::write(&share::to_rle(&rle, 7), "");
+        let (macrocell, generation) = load("a", &written).unwrap();
+        assert_eq!(generation, 7);
+        assert_eq!(macrocell.sorted_cells(), rle.sorted_cells());
+        assert!(matches!(load("a.rle", "3?"), Err(Error::Pattern(_))));
+        assert!(matches!(load("a.mc", "[M2]\nx"), Err(Error::Macrocell(_))));
+        assert_eq!(
+            load("a.mc", "[M2]\n1 0 200 0 0\n").err(),
+            Some(Error::Pattern(share::Error::State(200)))
+        );
+    }
+}
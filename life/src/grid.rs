@@ -0,0 +1,299 @@
+//! Tilings of the plane by squares, hexagons, or triangles. Cells of every
+//! tiling are indexed by row and column, as on the square grid; each tiling
+//! gives them its own neighbors and its own shape on the canvas.
+
+use std::fmt;
+
+use system::f64_to_i32_saturating;
+
+use crate::universe::{Bounds, Point};
+
+/// The shape of the cells, which determines their neighbors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Grid {
+    /// Eight neighbors, sharing an edge or a corner.
+    #[default]
+    Square,
+    /// Six neighbors. Each row is drawn half a cell left of the one above, so
+    /// that the square grid's neighbors above and to the right, and below and
+    /// to the left, aren't adjacent.
+    Hexagonal,
+    /// Twelve neighbors, sharing an edge or a corner. Cells whose row and
+    /// column sum to an even number point up, and the rest point down.
+    Triangular,
+}
+
+/// Offsets `(di, dj)` of the neighbors of a cell on each grid.
+const SQUARE: [(i32, i32); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+const HEXAGONAL: [(i32, i32); 6] = [(-1, -1), (-1, 0), (0, -1), (0, 1), (1, 0), (1, 1)];
+/// A triangle pointing up touches three cells above its apex, and five below
+/// its base.
+const TRIANGLE_UP: [(i32, i32); 12] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -2),
+    (0, -1),
+    (0, 1),
+    (0, 2),
+    (1, -2),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (1, 2),
+];
+const TRIANGLE_DOWN: [(i32, i32); 12] = [
+    (-1, -2),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (-1, 2),
+    (0, -2),
+    (0, -1),
+    (0, 1),
+    (0, 2),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// Distance from the center of a hexagon to its top or bottom vertex, in rows.
+/// Rows are one cell apart, so hexagons overlap the rows above and below by a
+/// sixth of a cell.
+const HEX_APEX: f64 = 2.0 / 3.0;
+
+/// Returns true if the triangle at `p` points up.
+fn points_up(p: Point) -> bool {
+    (p.i + p.j).rem_euclid(2) == 0
+}
+
+impl Grid {
+    /// The farthest any neighbor is from a cell, in rows or columns.
+    pub const REACH: i32 = 2;
+
+    /// Returns the offsets of the neighbors of the specified cell.
+    pub fn neighbors(self, p: Point) -> &'static [(i32, i32)] {
+        match self {
+            Grid::Square => &SQUARE,
+            Grid::Hexagonal => &HEXAGONAL,
+            Grid::Triangular if points_up(p) => &TRIANGLE_UP,
+            Grid::Triangular => &TRIANGLE_DOWN,
+        }
+    }
+
+    /// Returns the number of neighbors of every cell.
+    pub fn neighbor_count(self) -> u8 {
+        match self {
+            Grid::Square => 8,
+            Grid::Hexagonal => 6,
+            Grid::Triangular => 12,
+        }
+    }
+
+    /// Returns the letter that follows a rule on this grid, as in `B2/S34H`.
+    pub fn suffix(self) -> Option<char> {
+        match self {
+            Grid::Square => None,
+            Grid::Hexagonal => Some('H'),
+            Grid::Triangular => Some('L'),
+        }
+    }
+
+    pub fn from_suffix(c: char) -> Option<Grid> {
+        [Grid::Hexagonal, Grid::Triangular]
+            .into_iter()
+            .find(|grid| grid.suffix() == Some(c.to_ascii_uppercase()))
+    }
+
+    /// Returns how far right each row is drawn, in columns.
+    pub fn skew(self, i: i32) -> f64 {
+        match self {
+            Grid::Hexagonal => -f64::from(i) / 2.0,
+            Grid::Square | Grid::Triangular => 0.0,
+        }
+    }
+
+    /// Returns the vertices of the cell, in universe coordinates `(x, y)`,
+    /// where cells on the square grid span one unit each way.
+    pub fn outline(self, p: Point) -> Vec<(f64, f64)> {
+        let (x, y) = (f64::from(p.j) + self.skew(p.i), f64::from(p.i));
+        match self {
+            Grid::Square => vec![(x, y), (x + 1.0, y), (x + 1.0, y + 1.0), (x, y + 1.0)],
+            Grid::Hexagonal => {
+                let (cx, cy) = (x + 0.5, y + 0.5);
+                let (near, far) = (HEX_APEX / 2.0, HEX_APEX);
+                vec![
+                    (cx, cy - far),
+                    (cx + 0.5, cy - near),
+                    (cx + 0.5, cy + near),
+                    (cx, cy + far),
+                    (cx - 0.5, cy + near),
+                    (cx - 0.5, cy - near),
+                ]
+            }
+            // Triangles are two columns wide, overlapping their neighbors in
+            // the same row by half.
+            Grid::Triangular if points_up(p) => {
+                vec![(x + 0.5, y), (x + 1.5, y + 1.0), (x - 0.5, y + 1.0)]
+            }
+            Grid::Triangular => vec![(x - 0.5, y), (x + 1.5, y), (x + 0.5, y + 1.0)],
+        }
+    }
+
+    /// Returns the cell covering the specified universe coordinates.
+    pub fn cell_at(self, (x, y): (f64, f64)) -> Point {
+        let i = f64_to_i32_saturating(y.floor());
+        match self {
+            Grid::Square => Point {
+                i,
+                j: f64_to_i32_saturating(x.floor()),
+            },
+            Grid::Hexagonal => {
+                // Hexagons overlap rows, so the nearest center may be in the
+                // row above or below. Scaling rows makes the hexagons regular,
+                // so that each is the set of points nearest its center.
+                let scale = 1.0 / (3f64.sqrt() * HEX_APEX);
+                let distance = |p: Point| {
+                    let dx = x - (f64::from(p.j) + self.skew(p.i) + 0.5);
+                    let dy = (y - (f64::from(p.i) + 0.5)) * scale;
+                    dx * dx + dy * dy
+                };
+                (i - 1..=i + 1)
+                    .flat_map(|i| {
+                        let j = f64_to_i32_saturating((x - self.skew(i)).floor());
+                        [Point { i, j: j - 1 }, Point { i, j }, Point { i, j: j + 1 }]
+                    })
+                    .min_by(|&p, &q| distance(p).total_cmp(&distance(q)))
+                    .unwrap_or_default()
+            }
+            Grid::Triangular => {
+                // The edge between a cell and the next one in its row leans
+                // right if the cell points up, or left if it points down.
+                let fy = y - f64::from(i);
+                let edge = |j: i32| {
+                    let lean = if points_up(Point { i, j }) {
+                        fy
+                    } else {
+                        1.0 - fy
+                    };
+                    f64::from(j) + 0.5 + lean
+                };
+                let j = f64_to_i32_saturating(x.floor());
+                let j = (j - 1..=j + 1)
+                    .find(|&j| edge(j - 1) <= x && x < edge(j))
+                    .unwrap_or(j);
+                Point { i, j }
+            }
+        }
+    }
+
+    /// Returns bounds containing every cell drawn at least partly within the
+    /// specified cells of the square grid.
+    pub fn covering(self, bounds: Bounds) -> Bounds {
+        let Bounds { min, max } = bounds;
+        match self {
+            Grid::Square => bounds,
+            // Hexagons reach into the rows above and below, and each row is
+            // drawn left by half its index.
+            Grid::Hexagonal => {
+                let (top, bottom) = (min.i - 1, max.i + 1);
+                Bounds {
+                    min: Point {
+                        i: top,
+                        j: min.j + top.div_euclid(2) - 1,
+                    },
+                    max: Point {
+                        i: bottom,
+                        j: max.j + (bottom + 1).div_euclid(2) + 1,
+                    },
+                }
+            }
+            Grid::Triangular => Bounds {
+                min: Point {
+                    i: min.i,
+                    j: min.j - 1,
+                },
+                max: Point {
+                    i: max.i,
+                    j: max.j + 1,
+                },
+            },
+        }
+    }
+}
+
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Grid::Square => "Square",
+            Grid::Hexagonal => "Hexagonal",
+            Grid::Triangular => "Triangular",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRIDS: [Grid; 3] = [Grid::Square, Grid::Hexagonal, Grid::Triangular];
+
+    fn cells() -> impl Iterator<Item = Point> {
+        (-5..5).flat_map(|i| (-5..5).map(move |j| Point { i, j }))
+    }
+
+    #[test]
+    fn neighbors_are_mutual() {
+        for grid in GRIDS {
+            for p in cells() {
+                let neighbors = grid.neighbors(p);
+                assert_eq!(neighbors.len(), usize::from(grid.neighbor_count()));
+                for &(di, dj) in neighbors {
+                    assert!(di.abs().max(dj.abs()) <= Grid::REACH);
+                    let q = Point {
+                        i: p.i + di,
+                        j: p.j + dj,
+                    };
+                    assert!(grid.neighbors(q).contains(&(-di, -dj)), "{grid} {p:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn each_cell_is_hit_at_its_center() {
+        for grid in GRIDS {
+            for p in cells() {
+                let outline = grid.outline(p);
+                let n = f64::from(u8::try_from(outline.len()).unwrap());
+                let x = outline.iter().map(|&(x, _)| x).sum::<f64>() / n;
+                let y = outline.iter().map(|&(_, y)| y).sum::<f64>() / n;
+                assert_eq!(grid.cell_at((x, y)), p, "{grid}");
+                // The cell drawn in the middle of a square is covered by it.
+                let middle = (f64::from(p.j) + 0.5, f64::from(p.i) + 0.5);
+                let square = Bounds { min: p, max: p };
+                assert!(grid.covering(square).contains(grid.cell_at(middle)));
+            }
+        }
+        // Near the lower corner of a hexagon, the hexagons below meet it.
+        let hex = Grid::Hexagonal;
+        assert_eq!(hex.cell_at((0.5, 1.1)), Point { i: 0, j: 0 });
+        assert_eq!(hex.cell_at((0.05, 1.1)), Point { i: 1, j: 0 });
+        assert_eq!(hex.cell_at((0.95, 1.1)), Point { i: 1, j: 1 });
+        // Near the base of an upward triangle, its neighbors lean in.
+        let triangle = Grid::Triangular;
+        assert_eq!(triangle.cell_at((0.5, 0.9)), Point { i: 0, j: 0 });
+        assert_eq!(triangle.cell_at((0.2, 0.1)), Point { i: 0, j: -1 });
+        assert_eq!(triangle.cell_at((1.2, 0.9)), Point { i: 0, j: 0 });
+        assert_eq!(triangle.cell_at((1.2, 0.1)), Point { i: 0, j: 1 });
+    }
+}
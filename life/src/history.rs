@@ -0,0 +1,237 @@
+//! Past generations, kept as occasional copies of the universe, called
+//! keyframes, each followed by the cells that changed in later generations.
+
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+
+use crate::universe::{Cell, Point, Universe};
+
+/// The default memory limit, in bytes.
+pub const DEFAULT_LIMIT: usize = 64 << 20;
+
+/// The most generations replayed to reach any generation, which bounds the
+/// time taken to seek.
+const MAX_REPLAY: usize = 100;
+
+struct Keyframe {
+    generation: u32,
+    universe: Universe,
+    /// The cells that changed in each generation after the keyframe, with
+    /// their new states.
+    diffs: Vec<Vec<(Point, Cell)>>,
+    /// Estimated bytes used by the universe and diffs.
+    footprint: usize,
+}
+
+impl Keyframe {
+    fn new(universe: &Universe, generation: u32) -> Keyframe {
+        Keyframe {
+            generation,
+            universe: universe.clone(),
+            diffs: Vec::new(),
+            footprint: universe.footprint(),
+        }
+    }
+
+    fn last(&self) -> u32 {
+        self.generation + system::usize_to_u32(self.diffs.len())
+    }
+}
+
+/// A bounded record of recent generations, from which any of them can be
+/// restored. When the memory limit is reached, the oldest generations are
+/// forgotten, but the latest keyframe is always kept.
+pub struct History {
+    keyframes: VecDeque<Keyframe>,
+    limit: usize,
+    /// Estimated bytes used by all keyframes.
+    used: usize,
+    /// The revision of the universe as of the latest recorded generation.
+    revision: u64,
+}
+
+impl History {
+    pub fn new(limit: usize) -> History {
+        History {
+            keyframes: VecDeque::new(),
+            limit,
+            used: 0,
+            revision: 0,
+        }
+    }
+
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.enforce_limit();
+    }
+
+    pub fn clear(&mut self) {
+        self.keyframes.clear();
+        self.used = 0;
+    }
+
+    /// Returns the first and last generations that can be restored, if any.
+    pub fn range(&self) -> Option<RangeInclusive<u32>> {
+        let first = self.keyframes.front()?.generation;
+        let last = self.keyframes.back()?.last();
+        Some(first..=last)
+    }
+
+    /// Makes the specified generation the latest, recording it if the universe
+    /// has been edited since it was recorded. Later generations are forgotten.
+    /// Call this before running the universe, with its current generation.
+    pub fn sync(&mut self, universe: &Universe, generation: u32) {
+        let range = self.range();
+        if universe.revision() == self.revision
+            && range
+                .as_ref()
+                .is_some_and(|range| range.contains(&generation))
+        {
+            self.truncate(generation + 1);
+            return;
+        }
+        if range.is_some_and(|range| range.contains(&generation.saturating_sub(1))) {
+            self.truncate(generation);
+        } else {
+            self.clear();
+        }
+        self.push_keyframe(universe, generation);
+    }
+
+    /// Records the generation after the latest, given the universe at that
+    /// generation and the cells that changed to reach it. Does nothing if
+    /// [`Self::sync`] was not called first.
+    pub fn record(&mut self, universe: &Universe, changed: Vec<(Point, Cell)>) {
+        let Some(keyframe) = self.keyframes.back_mut() else {
+            return;
+        };
+        let generation = keyframe.last() + 1;
+        let bytes = size_of_val(changed.as_slice()) + size_of::<Vec<(Point, Cell)>>();
+        // A new keyframe is cheaper than replaying diffs larger than one.
+        if keyframe.diffs.len() < MAX_REPLAY
+            && keyframe.footprint + bytes < 2 * keyframe.universe.footprint()
+        {
+            keyframe.diffs.push(changed);
+            keyframe.footprint += bytes;
+            self.used += bytes;
+            self.enforce_limit();
+        } else {
+            self.push_keyframe(universe, generation);
+        }
+    }
+
+    /// Returns the universe at the specified generation, if it is recorded.
+    pub fn seek(&mut self, generation: u32) -> Option<Universe> {
+        let keyframe = self
+            .keyframes
+            .iter()
+            .rfind(|keyframe| keyframe.generation <= generation)
+            .filter(|keyframe| generation <= keyframe.last())?;
+        let mut universe = keyframe.universe.clone();
+        let replay = system::u32_to_usize(generation - keyframe.generation);
+        for &(p, c) in keyframe.diffs[..replay].iter().flatten() {
+            universe.set(p, c);
+        }
+        self.revision = universe.revision();
+        Some(universe)
+    }
+
+    /// Forgets the specified generation and all later ones.
+    fn truncate(&mut self, generation: u32) {
+        while let Some(keyframe) = self.keyframes.back_mut() {
+            if keyframe.generation >= generation {
+                self.used -= keyframe.footprint;
+                self.keyframes.pop_back();
+                continue;
+            }
+            let keep = system::u32_to_usize(generation - keyframe.generation - 1);
+            for diff in keyframe.diffs.drain(keep.min(keyframe.diffs.len())..) {
+                let bytes = size_of_val(diff.as_slice()) + size_of::<Vec<(Point, Cell)>>();
+                keyframe.footprint -= bytes;
+                self.used -= bytes;
+            }
+            break;
+        }
+    }
+
+    fn push_keyframe(&mut self, universe: &Universe, generation: u32) {
+        let keyframe = Keyframe::new(universe, generation);
+        self.used += keyframe.footprint;
+        self.keyframes.push_back(keyframe);
+        self.revision = universe.revision();
+        self.enforce_limit();
+    }
+
+    fn enforce_limit(&mut self) {
+        while self.used > self.limit && self.keyframes.len() > 1 {
+            if let Some(keyframe) = self.keyframes.pop_front() {
+                self.used -= keyframe.footprint;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soup::Soup;
+
+    /// Runs the universe for the specified number of generations, recording
+    /// each, and returns a copy of its cells at each generation.
+    fn run(universe: &mut Universe, history: &mut History, generations: u32) -> Vec<Vec<Point>> {
+        let mut copies = vec![universe.sorted_cells()];
+        for generation in 0..generations {
+            history.sync(universe, generation);
+            let mut changed = Vec::new();
+            universe.tick_with(|p, c| changed.push((p, c)));
+            history.record(universe, changed);
+            copies.push(universe.sorted_cells());
+        }
+        copies
+    }
+
+    #[test]
+    fn seeks_any_recorded_generation() {
+        let mut universe = Soup::default().generate();
+        let mut history = History::new(DEFAULT_LIMIT);
+        let copies = run(&mut universe, &mut history, 300);
+        assert_eq!(history.range(), Some(0..=300));
+        assert!(history.keyframes.len() >= 3);
+        for generation in [0, 1, 99, 100, 101, 250, 300] {
+            let past = history.seek(generation).unwrap();
+            assert_eq!(
+                past.sorted_cells(),
+                copies[system::u32_to_usize(generation)]
+            );
+        }
+        assert!(history.seek(301).is_none());
+    }
+
+    #[test]
+    fn resumes_from_the_past_and_forgets_the_future() {
+        let mut universe = Soup::default().generate();
+        let mut history = History::new(DEFAULT_LIMIT);
+        run(&mut universe, &mut history, 50);
+        let mut past = history.seek(20).unwrap();
+        history.sync(&past, 20);
+        assert_eq!(history.range(), Some(0..=20));
+        // Edits also replace the recorded generation.
+        past.set(Point { i: -9, j: -9 }, Cell::LIVE);
+        history.sync(&past, 20);
+        assert_eq!(history.range(), Some(0..=20));
+        let restored = history.seek(20).unwrap();
+        assert_eq!(restored.at(Point { i: -9, j: -9 }), Cell::LIVE);
+    }
+
+    #[test]
+    fn memory_is_limited() {
+        let mut universe = Soup::default().generate();
+        let mut history = History::new(0);
+        run(&mut universe, &mut history, 300);
+        assert_eq!(history.keyframes.len(), 1);
+        let range = history.range().unwrap();
+        assert!(*range.start() > 0 && *range.end() == 300);
+        history.set_limit(DEFAULT_LIMIT);
+        assert_eq!(history.range(), Some(range));
+    }
+}
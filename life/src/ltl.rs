@@ -0,0 +1,377 @@
+//! Larger than Life: rules like Life's, but counting the live cells in a
+//! neighborhood of up to ten rows and columns each way, in the notation Kellie
+//! Evans devised and Golly uses, such as `R5,C0,M1,S34..58,B34..45,NM` for
+//! Bosco's rule.
+//!
+//! Counting hundreds of neighbors one by one would be far too slow, so counts
+//! come from a summed-area table: the number of live cells above and to the
+//! left of each point, from which any rectangle's count takes four lookups.
+
+use std::fmt;
+
+use crate::rule::{Error, MAX_STATES};
+use crate::universe::Cell;
+
+/// The farthest a neighborhood reaches, in rows or columns.
+pub const MAX_RANGE: i32 = 10;
+
+/// The shape of a neighborhood.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// A square.
+    #[default]
+    Moore,
+    /// A diamond: cells at most the range away, in rows plus columns.
+    VonNeumann,
+    /// Cells within the range of the center, plus half a cell, as the crow
+    /// flies.
+    Circular,
+}
+
+impl Neighborhood {
+    const ALL: [Neighborhood; 3] = [
+        Neighborhood::Moore,
+        Neighborhood::VonNeumann,
+        Neighborhood::Circular,
+    ];
+
+    /// Returns the letter following `N` in the notation.
+    fn letter(self) -> char {
+        match self {
+            Neighborhood::Moore => 'M',
+            Neighborhood::VonNeumann => 'N',
+            Neighborhood::Circular => 'C',
+        }
+    }
+
+    fn from_letter(s: &str) -> Option<Neighborhood> {
+        Neighborhood::ALL
+            .into_iter()
+            .find(|n| s.eq_ignore_ascii_case(n.letter().encode_utf8(&mut [0; 4])))
+    }
+
+    /// Returns how many columns the neighborhood reaches either way, in the
+    /// row `di` rows from the center.
+    fn reach(self, range: i32, di: i32) -> i32 {
+        match self {
+            Neighborhood::Moore => range,
+            Neighborhood::VonNeumann => range - di.abs(),
+            // Within `range + 1/2`, which for whole numbers is `range² + range`.
+            Neighborhood::Circular => (range * range + range - di * di).isqrt(),
+        }
+    }
+}
+
+/// A Larger than Life rule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ltl {
+    range: u8,
+    /// The number of states, including dead. As in Generations rules, live
+    /// cells that don't survive pass through dying states.
+    states: u8,
+    /// Whether each cell counts itself among its neighbors.
+    middle: bool,
+    /// The least and most live neighbors with which a live cell survives.
+    survival: (u16, u16),
+    /// The least and most live neighbors with which a dead cell is born.
+    birth: (u16, u16),
+    neighborhood: Neighborhood,
+}
+
+impl Ltl {
+    /// Returns the farthest any neighbor is from a cell, in rows or columns.
+    pub fn range(self) -> i32 {
+        i32::from(self.range)
+    }
+
+    pub fn states(self) -> u8 {
+        self.states
+    }
+
+    /// Returns the number of cells counted for each cell.
+    fn neighbor_count(self) -> u16 {
+        let range = self.range();
+        let cells: i32 = (-range..=range)
+            .map(|di| 2 * self.neighborhood.reach(range, di) + 1)
+            .sum();
+        u16::try_from(cells).unwrap_or_default() - u16::from(!self.middle)
+    }
+
+    /// Returns the next state of a cell, where `c` is its current state and
+    /// `n` its number of live neighbors.
+    pub fn next(self, c: Cell, n: u16) -> Cell {
+        let within = |(min, max): (u16, u16)| (min..=max).contains(&n);
+        match c.0 {
+            0 => Cell(u8::from(within(self.birth))),
+            1 if within(self.survival) => Cell::LIVE,
+            state => {
+                let next = state.saturating_add(1);
+                Cell(if next < self.states { next } else { 0 })
+            }
+        }
+    }
+}
+
+/// Parses a range of neighbor counts, such as `34..58`, after its letter.
+fn parse_counts(part: &str, letter: char) -> Option<(u16, u16)> {
+    let (min, max) = part
+        .strip_prefix([letter, letter.to_ascii_lowercase()])?
+        .split_once("..")?;
+    let (min, max) = (min.parse().ok()?, max.parse().ok()?);
+    (min <= max).then_some((min, max))
+}
+
+/// Parses a rule in Larger than Life notation. The neighborhood may be left
+/// out, for Moore's. `C0` and `C1`, like `C2`, mean two states.
+///
+/// # Errors
+///
+/// Will return [`Err`] if the rule is malformed, reaches too far, or has
+/// births from no neighbors.
+pub fn parse(s: &str) -> Result<Ltl, Error> {
+    let syntax = || Error::Syntax(s.to_owned());
+    let mut parts = s.split(',').map(str::trim);
+    let mut number = |letter: char| -> Option<u8> {
+        parts
+            .next()?
+            .strip_prefix([letter, letter.to_ascii_lowercase()])?
+            .parse()
+            .ok()
+    };
+    let range = number('R')
+        .filter(|&range| (1..=MAX_RANGE).contains(&i32::from(range)))
+        .ok_or_else(syntax)?;
+    let states = number('C')
+        .filter(|&states| states <= MAX_STATES)
+        .ok_or_else(syntax)?
+        .max(2);
+    let middle = match number('M') {
+        Some(0) => false,
+        Some(1) => true,
+        _ => return Err(syntax()),
+    };
+    let survival = parts.next().and_then(|part| parse_counts(part, 'S'));
+    let birth = parts.next().and_then(|part| parse_counts(part, 'B'));
+    let (Some(survival), Some(birth)) = (survival, birth) else {
+        return Err(syntax());
+    };
+    let neighborhood = match parts.next() {
+        None => Neighborhood::Moore,
+        Some(part) => part
+            .strip_prefix(['N', 'n'])
+            .and_then(Neighborhood::from_letter)
+            .ok_or_else(syntax)?,
+    };
+    if parts.next().is_some() {
+        return Err(syntax());
+    }
+    let ltl = Ltl {
+        range,
+        states,
+        middle,
+        survival,
+        birth,
+        neighborhood,
+    };
+    if survival.1.max(birth.1) > ltl.neighbor_count() {
+        return Err(syntax());
+    }
+    if birth.0 == 0 {
+        return Err(Error::BirthFromNothing);
+    }
+    Ok(ltl)
+}
+
+impl fmt::Display for Ltl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Ltl {
+            range,
+            states,
+            middle,
+            survival: (s0, s1),
+            birth: (b0, b1),
+            neighborhood,
+        } = *self;
+        // Golly writes two states as `C0`.
+        let states = if states == 2 { 0 } else { states };
+        write!(
+            f,
+            "R{range},C{states},M{},S{s0}..{s1},B{b0}..{b1},N{}",
+            u8::from(middle),
+            neighborhood.letter()
+        )
+    }
+}
+
+/// A summed-area table of a window of cells, one byte per cell: 1 if live,
+/// otherwise 0.
+pub struct Sums {
+    /// One more than the width of the window.
+    stride: usize,
+    /// The number of live cells above and to the left of each point between
+    /// cells, including the edges of the window.
+    sums: Vec<u16>,
+}
+
+impl Sums {
+    pub fn new(window: &[u8], width: usize) -> Sums {
+        let stride = width + 1;
+        let mut sums = vec![0; stride * (window.len() / width + 1)];
+        for (i, row) in window.chunks_exact(width).enumerate() {
+            let mut run = 0;
+            for (j, &byte) in row.iter().enumerate() {
+                run += u16::from(byte);
+                sums[(i + 1) * stride + j + 1] = sums[i * stride + j + 1] + run;
+            }
+        }
+        Sums { stride, sums }
+    }
+
+    /// Returns the number of live cells in the rows `top..bottom` and columns
+    /// `left..right`.
+    fn rectangle(&self, top: usize, bottom: usize, left: usize, right: usize) -> u16 {
+        let at = |i: usize, j: usize| self.sums[i * self.stride + j];
+        (at(bottom, right) - at(top, right)) - (at(bottom, left) - at(top, left))
+    }
+}
+
+/// Counts the live neighbors of cells, from the summed-area table of their
+/// window.
+pub struct Counter {
+    range: usize,
+    /// Runs of rows that reach equally far either way, as the first row and one
+    /// past the last, counting from the top of the neighborhood, and the reach.
+    bands: Vec<(usize, usize, usize)>,
+    middle: bool,
+}
+
+impl Counter {
+    pub fn new(ltl: Ltl) -> Counter {
+        let range = ltl.range();
+        let mut bands: Vec<(usize, usize, usize)> = Vec::new();
+        for (row, di) in (-range..=range).enumerate() {
+            let reach = usize::try_from(ltl.neighborhood.reach(range, di)).unwrap_or_default();
+            match bands.last_mut() {
+                Some((_, end, last)) if *last == reach => *end = row + 1,
+                _ => bands.push((row, row + 1, reach)),
+            }
+        }
+        Counter {
+            range: usize::from(ltl.range),
+            bands,
+            middle: ltl.middle,
+        }
+    }
+
+    /// Returns the number of live neighbors of the cell at row `i` and column
+    /// `j` of the window, which must be at least the range from its edges.
+    pub fn count(&self, sums: &Sums, window: &[u8], width: usize, i: usize, j: usize) -> u16 {
+        let top = i - self.range;
+        let n: u16 = self
+            .bands
+            .iter()
+            .map(|&(first, end, reach)| {
+                sums.rectangle(top + first, top + end, j - reach, j + reach + 1)
+            })
+            .sum();
+        if self.middle {
+            n
+        } else {
+            n - u16::from(window[i * width + j])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use math::LinearCongruentialGenerator;
+
+    use super::*;
+    use crate::rule::Rule;
+
+    #[test]
+    fn notation() {
+        let bosco = "R5,C0,M1,S34..58,B34..45,NM";
+        let ltl = parse(bosco).unwrap();
+        assert_eq!(ltl.to_string(), bosco);
+        assert_eq!(ltl.range(), 5);
+        assert_eq!(ltl.neighbor_count(), 121);
+        assert_eq!(bosco.parse(), Ok(Rule::Larger(ltl)));
+        assert_eq!(Rule::Larger(ltl).to_string(), bosco);
+        assert_eq!(parse("r5,c2,m1,s34..58,b34..45"), Ok(ltl));
+        let diamond = parse("R2,C3,M0,S1..2,B2..3,NN").unwrap();
+        assert_eq!(diamond.neighbor_count(), 12);
+        assert_eq!(diamond.states(), 3);
+        assert_eq!(diamond.next(Cell::LIVE, 3), Cell(2));
+        // Within 1.5 cells, a circle is a square without its corners.
+        assert_eq!(
+            parse("R1,C0,M0,S1..1,B1..1,NC").unwrap().neighbor_count(),
+            8
+        );
+        assert_eq!(
+            parse("R2,C0,M1,S1..1,B1..1,NC").unwrap().neighbor_count(),
+            21
+        );
+        for bad in [
+            "R11,C0,M1,S1..1,B1..1,NM",
+            "R0,C0,M1,S1..1,B1..1,NM",
+            "R1,C0,M2,S1..1,B1..1,NM",
+            "R1,C0,M1,S3..2,B1..1,NM",
+            "R1,C0,M1,S1..10,B1..1,NM",
+            "R1,C0,M1,S1..1,B1..1,NX",
+            "R1,C0,M1,S1..1,B1..1,NM,X",
+            "R1,C0,M1,S1..1",
+        ] {
+            assert!(matches!(parse(bad), Err(Error::Syntax(_))), "{bad}");
+        }
+        assert_eq!(
+            parse("R1,C0,M1,S1..1,B0..1,NM"),
+            Err(Error::BirthFromNothing)
+        );
+    }
+
+    #[test]
+    fn sums_count_like_brute_force() {
+        const WIDTH: usize = 32;
+        let mut random = LinearCongruentialGenerator::from_seed(7);
+        let window: Vec<u8> = (0..WIDTH * WIDTH)
+            .map(|_| u8::from(random.next_u32() < u32::MAX / 3))
+            .collect();
+        let sums = Sums::new(&window, WIDTH);
+        for rule in [
+            "R1,C0,M0,S2..3,B3..3,NM",
+            "R5,C0,M1,S34..58,B34..45,NM",
+            "R3,C0,M0,S1..1,B1..1,NN",
+            "R7,C0,M1,S1..1,B1..1,NC",
+            "R10,C0,M0,S1..1,B1..1,NC",
+        ] {
+            let ltl = parse(rule).unwrap();
+            let counter = Counter::new(ltl);
+            let range = ltl.range();
+            let r = counter.range;
+            for i in r..WIDTH - r {
+                for j in r..WIDTH - r {
+                    let mut expected = 0;
+                    for di in -range..=range {
+                        for dj in -range..=range {
+                            let within = match ltl.neighborhood {
+                                Neighborhood::Moore => true,
+                                Neighborhood::VonNeumann => di.abs() + dj.abs() <= range,
+                                Neighborhood::Circular => {
+                                    di * di + dj * dj <= range * range + range
+                                }
+                            };
+                            if within && (ltl.middle || (di, dj) != (0, 0)) {
+                                let i = i.strict_add_signed(isize::try_from(di).unwrap());
+                                let j = j.strict_add_signed(isize::try_from(dj).unwrap());
+                                expected += u16::from(window[i * WIDTH + j]);
+                            }
+                        }
+                    }
+                    let n = counter.count(&sums, &window, WIDTH, i, j);
+                    assert_eq!(n, expected, "{rule} at {i}, {j}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,437 @@
+//! Golly's [macrocell] pattern format, which stores the quadtree of a pattern
+//! with each distinct node written once, so that huge, repetitive patterns
+//! stay small.
+//!
+//! [macrocell]: https://conwaylife.com/wiki/Macrocell
+
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+use crate::pattern::Pattern;
+use crate::rle::Rle;
+use crate::universe::{Cell, Point};
+
+/// The first line of every macrocell file begins with this.
+pub const MAGIC: &str = "[M2]";
+
+/// The most cells read from a file. Shared nodes let a few lines describe far
+/// more cells than the universe can hold.
+const MAX_CELLS: u64 = 1 << 24;
+
+/// The deepest node read. Its side, `2^level`, must fit in an `i64`.
+const MAX_LEVEL: u32 = 62;
+
+/// Two-state leaves are 8 by 8 squares, at this level.
+const LEAF_LEVEL: u32 = 3;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The first line was not `[M2]`.
+    Header(String),
+    /// A line was neither a comment, a leaf, nor a node.
+    Line(String),
+    /// A node referred to one not defined before it, or at the wrong level.
+    Node(String),
+    /// The pattern held cells beyond the edges of the universe, or more cells
+    /// than it can hold.
+    TooLarge,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Header(line) => write!(f, "bad macrocell header: {line}"),
+            Error::Line(line) => write!(f, "bad macrocell line: {line}"),
+            Error::Node(line) => write!(f, "bad macrocell node: {line}"),
+            Error::TooLarge => write!(f, "macrocell pattern is too large"),
+        }
+    }
+}
+
+/// A square of cells, `2^level` on a side.
+enum Node {
+    /// An 8 by 8 square of two-state cells, as the row and column of each live
+    /// cell.
+    Leaf(Vec<(i64, i64)>),
+    /// A 2 by 2 square of cells in any state, in the same order as children.
+    Cells([Cell; 4]),
+    /// Indexes of the top left, top right, bottom left, and bottom right
+    /// quarters, counting nodes from 1, where 0 is empty.
+    Branch { level: u32, children: [usize; 4] },
+}
+
+impl Node {
+    fn level(&self) -> u32 {
+        match self {
+            Node::Leaf(_) => LEAF_LEVEL,
+            Node::Cells(_) => 1,
+            Node::Branch { level, .. } => *level,
+        }
+    }
+}
+
+/// Parses a leaf line, such as `$$..*$...*$.***$`, whose rows each end with
+/// `$`, and in which `*` is a live cell.
+fn parse_leaf(line: &str) -> Option<Node> {
+    let (mut i, mut j) = (0, 0);
+    let mut cells = Vec::new();
+    for c in line.chars() {
+        match c {
+            '.' => j += 1,
+            '*' => {
+                cells.push((i, j));
+                j += 1;
+            }
+            '$' => (i, j) = (i + 1, 0),
+            _ => return None,
+        }
+        if i > 8 || j > 8 || (i == 8 && j > 0) {
+            return None;
+        }
+    }
+    Some(Node::Leaf(cells))
+}
+
+/// Parses a node line, such as `4 1 2 0 3`, giving the level and then the
+/// children, or for level 1, the states of the four cells.
+fn parse_node(line: &str, nodes: &[Node]) -> Result<Node, Error> {
+    let bad_line = || Error::Line(line.to_owned());
+    let numbers = line.split_whitespace().map(str::parse::<usize>);
+    let numbers: Vec<usize> = numbers.collect::<Result<_, _>>().map_err(|_| bad_line())?;
+    let &[level, a, b, c, d] = numbers.as_slice() else {
+        return Err(bad_line());
+    };
+    let level = u32::try_from(level)
+        .ok()
+        .filter(|level| (1..=MAX_LEVEL).contains(level))
+        .ok_or_else(bad_line)?;
+    if level == 1 {
+        let state = |n: usize| u8::try_from(n).map(Cell).map_err(|_| bad_line());
+        return Ok(Node::Cells([state(a)?, state(b)?, state(c)?, state(d)?]));
+    }
+    for child in [a, b, c, d].into_iter().filter(|&child| child != 0) {
+        let fits = nodes
+            .get(child - 1)
+            .is_some_and(|node| node.level() == level - 1);
+        if !fits {
+            return Err(Error::Node(line.to_owned()));
+        }
+    }
+    Ok(Node::Branch {
+        level,
+        children: [a, b, c, d],
+    })
+}
+
+/// Returns the number of cells that aren't dead in each node.
+fn populations(nodes: &[Node]) -> Vec<u64> {
+    let mut populations: Vec<u64> = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let population = match node {
+            Node::Leaf(cells) => cells.iter().map(|_| 1).sum(),
+            Node::Cells(cells) => cells.iter().filter(|&&c| c != Cell::DEAD).map(|_| 1).sum(),
+            Node::Branch { children, .. } => children
+                .iter()
+                .filter(|&&child| child != 0)
+                .map(|&child| populations[child - 1])
+                .fold(0, u64::saturating_add),
+        };
+        populations.push(population);
+    }
+    populations
+}
+
+/// Appends the cells of the node whose top left corner is at `(top, left)`.
+/// Empty children are skipped, since a few lines can nest them deeper than
+/// could ever be walked.
+fn expand(
+    nodes: &[Node],
+    populations: &[u64],
+    index: usize,
+    (top, left): (i64, i64),
+    cells: &mut Vec<(Point, Cell)>,
+) {
+    let mut push = |i: i64, j: i64, c: Cell| {
+        if let (Ok(i), Ok(j)) = (i32::try_from(top + i), i32::try_from(left + j)) {
+            cells.push((Point { i, j }, c));
+        }
+    };
+    match &nodes[index - 1] {
+        Node::Leaf(live) => {
+            for &(i, j) in live {
+                push(i, j, Cell::LIVE);
+            }
+        }
+        Node::Cells(states) => {
+            for (k, &c) in (0..).zip(states).filter(|&(_, &c)| c != Cell::DEAD) {
+                push(k / 2, k % 2, c);
+            }
+        }
+        Node::Branch { level, children } => {
+            let half = 1 << (level - 1);
+            let corners = [(0, 0), (0, half), (half, 0), (half, half)];
+            for (&child, (i, j)) in children.iter().zip(corners) {
+                if child != 0 && populations[child - 1] != 0 {
+                    expand(nodes, populations, child, (top + i, left + j), cells);
+                }
+            }
+        }
+    }
+}
+
+/// Parses a macrocell file. The root, which is the last node, is centered on
+/// the origin. Comments other than the rule (`#R`) and generation (`#G`) are
+/// ignored.
+///
+/// # Errors
+///
+/// Will return [`Err`] if the file is malformed, or holds cells the universe
+/// can't.
+pub fn parse(text: &str) -> Result<Rle, Error> {
+    let mut lines = text.lines().map(str::trim);
+    let header = lines.next().unwrap_or_default();
+    if !header.starts_with(MAGIC) {
+        return Err(Error::Header(header.to_owned()));
+    }
+    let mut rle = Rle::default();
+    let mut nodes = Vec::new();
+    for line in lines.filter(|line| !line.is_empty()) {
+        if let Some(comment) = line.strip_prefix('#') {
+            if let Some(rule) = comment.strip_prefix('R') {
+                rle.rule = Some(rule.trim().to_owned());
+            } else if let Some(generation) = comment.strip_prefix('G') {
+                rle.generation = generation.trim().parse().unwrap_or_default();
+            }
+        } else if line.starts_with(['.', '*', '$']) {
+            nodes.push(parse_leaf(line).ok_or_else(|| Error::Line(line.to_owned()))?);
+        } else {
+            nodes.push(parse_node(line, &nodes)?);
+        }
+    }
+    let Some(root) = nodes.last() else {
+        return Ok(rle);
+    };
+    let populations = populations(&nodes);
+    let population = populations.last().copied().unwrap_or_default();
+    if population > MAX_CELLS {
+        return Err(Error::TooLarge);
+    }
+    let corner = -(1i64 << (root.level() - 1));
+    let mut cells = Vec::new();
+    expand(
+        &nodes,
+        &populations,
+        nodes.len(),
+        (corner, corner),
+        &mut cells,
+    );
+    // Cells beyond the edges of the universe were left out.
+    if cells.iter().map(|_| 1).sum::<u64>() != population {
+        return Err(Error::TooLarge);
+    }
+    (rle.pattern, rle.origin) = Pattern::enclosing(cells);
+    Ok(rle)
+}
+
+/// Collects the lines of a macrocell file, writing each distinct node once.
+struct Writer {
+    lines: Vec<String>,
+    /// The index of each line, counting from 1.
+    indexes: HashMap<String, usize>,
+    /// Whether leaves are 8 by 8 squares of live cells, rather than 2 by 2
+    /// squares of cells in any state.
+    two_state: bool,
+}
+
+impl Writer {
+    /// Returns the index of the line for the node whose top left corner is at
+    /// `(top, left)`, holding the specified cells, or 0 if there are none.
+    fn node(&mut self, cells: &[(i64, i64, Cell)], level: u32, (top, left): (i64, i64)) -> usize {
+        if cells.is_empty() {
+            return 0;
+        }
+        let line = if self.two_state && level == LEAF_LEVEL {
+            let mut rows = [[false; 8]; 8];
+            for &(i, j, _) in cells {
+                rows[usize::try_from(i - top).unwrap_or_default()]
+                    [usize::try_from(j - left).unwrap_or_default()] = true;
+            }
+            let last = rows
+                .iter()
+                .rposition(|row| row.contains(&true))
+                .unwrap_or_default();
+            let mut line = String::new();
+            for row in &rows[..=last] {
+                let len = row.iter().rposition(|&live| live).map_or(0, |j| j + 1);
+                line.extend(row[..len].iter().map(|&live| if live { '*' } else { '.' }));
+                line.push('$');
+            }
+            line
+        } else if level == 1 {
+            let mut states = [Cell::DEAD; 4];
+            for &(i, j, c) in cells {
+                states[usize::try_from((i - top) * 2 + j - left).unwrap_or_default()] = c;
+            }
+            let [a, b, c, d] = states.map(|c| c.0);
+            format!("1 {a} {b} {c} {d}")
+        } else {
+            let half = 1 << (level - 1);
+            let corners = [(0, 0), (0, half), (half, 0), (half, half)];
+            let mut children = [0; 4];
+            for (child, (i, j)) in children.iter_mut().zip(corners) {
+                let (top, left) = (top + i, left + j);
+                let inside = |&&(ci, cj, _): &&(i64, i64, Cell)| {
+                    (top..top + half).contains(&ci) && (left..left + half).contains(&cj)
+                };
+                let quarter: Vec<_> = cells.iter().filter(inside).copied().collect();
+                *child = self.node(&quarter, level - 1, (top, left));
+            }
+            let [a, b, c, d] = children;
+            format!("{level} {a} {b} {c} {d}")
+        };
+        if let Some(&index) = self.indexes.get(&line) {
+            return index;
+        }
+        self.lines.push(line.clone());
+        self.indexes.insert(line, self.lines.len());
+        self.lines.len()
+    }
+}
+
+/// Returns the pattern as a macrocell file, with the specified comment lines,
+/// which must each begin with `#`, after the header. Cells are placed at the
+/// pattern's origin, in a root node centered on the origin of the universe.
+pub fn write(rle: &Rle, comments: &str) -> String {
+    let Rle {
+        pattern,
+        rule,
+        origin,
+        generation,
+    } = rle;
+    let two_state = pattern.max_state().0 <= 1;
+    let cells: Vec<(i64, i64, Cell)> = pattern
+        .states()
+        .map(|(p, c)| (i64::from(origin.i + p.i), i64::from(origin.j + p.j), c))
+        .collect();
+    // The smallest root centered on the origin that holds every cell.
+    let reach = cells
+        .iter()
+        .map(|&(i, j, _)| i.max(j).max(-1 - i.min(j)))
+        .max()
+        .unwrap_or_default();
+    let mut level = if two_state { LEAF_LEVEL } else { 1 };
+    while reach >= 1 << (level - 1) {
+        level += 1;
+    }
+    let corner = -(1i64 << (level - 1));
+    let mut writer = Writer {
+        lines: Vec::new(),
+        indexes: HashMap::new(),
+        two_state,
+    };
+    writer.node(&cells, level, (corner, corner));
+
+    let mut text = format!("{MAGIC} (life)\n{comments}");
+    // Writing to a string can't fail.
+    if let Some(rule) = rule {
+        _ = writeln!(text, "#R {rule}");
+    }
+    if *generation != 0 {
+        _ = writeln!(text, "#G {generation}");
+    }
+    for line in writer.lines {
+        _ = writeln!(text, "{line}");
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(rle: &Rle) -> Vec<(i32, i32, u8)> {
+        let mut cells: Vec<_> = rle
+            .pattern
+            .states()
+            .map(|(p, c)| (rle.origin.i + p.i, rle.origin.j + p.j, c.0))
+            .collect();
+        cells.sort_unstable();
+        cells
+    }
+
+    #[test]
+    fn round_trips_hand_made_files() {
+        let glider = "[M2] (life)\n#R B3/S23\n$$$$.....*$......*$....***$\n";
+        let rle = parse(glider).unwrap();
+        assert_eq!(
+            cells(&rle),
+            [(0, 1, 1), (1, 2, 1), (2, 0, 1), (2, 1, 1), (2, 2, 1)]
+        );
+        assert_eq!(rle.rule.as_deref(), Some("B3/S23"));
+        assert_eq!(write(&rle, ""), glider);
+
+        // Four copies of one leaf are written once.
+        let blocks = "[M2] (life)\n#R B3/S23\n#G 7\n$$$...**$...**$\n4 1 1 1 1\n";
+        let rle = parse(blocks).unwrap();
+        assert_eq!(rle.generation, 7);
+        assert_eq!(rle.pattern.states().count(), 16);
+        assert!(cells(&rle).contains(&(-5, -5, 1)));
+        assert!(cells(&rle).contains(&(4, 4, 1)));
+        assert_eq!(write(&rle, ""), blocks);
+
+        // Multi-state leaves are 2 by 2.
+        let brain = "[M2] (life)\n#R B2/S/C3\n1 0 1 2 0\n2 0 1 1 0\n";
+        let rle = parse(brain).unwrap();
+        assert_eq!(
+            cells(&rle),
+            [(-2, 1, 1), (-1, 0, 2), (0, -1, 1), (1, -2, 2)]
+        );
+        assert_eq!(write(&rle, ""), brain);
+    }
+
+    #[test]
+    fn writes_distant_cells_compactly() {
+        let far = [(0, 0), (1_000_000, -1_000_000), (-1_000_000_000, 7)];
+        let cells_in = far.map(|(i, j)| (Point { i, j }, Cell::LIVE));
+        let (pattern, origin) = Pattern::enclosing(cells_in);
+        let rle = Rle {
+            pattern,
+            origin,
+            ..Rle::default()
+        };
+        let text = write(&rle, "#C Far apart.\n");
+        assert!(text.starts_with("[M2] (life)\n#C Far apart.\n"));
+        assert!(text.lines().count() < 100);
+        assert_eq!(cells(&parse(&text).unwrap()), cells(&rle));
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        assert!(matches!(parse("#R B3/S23\n"), Err(Error::Header(_))));
+        assert!(matches!(parse("[M2]\nhello\n"), Err(Error::Line(_))));
+        assert!(matches!(parse("[M2]\n.........*$\n"), Err(Error::Line(_))));
+        // Children must come first, and be one level down.
+        assert!(matches!(parse("[M2]\n4 1 0 0 0\n"), Err(Error::Node(_))));
+        assert!(matches!(
+            parse("[M2]\n*$\n5 1 0 0 0\n"),
+            Err(Error::Node(_))
+        ));
+        // A 2^40 square is far wider than the universe.
+        let mut huge = "[M2]\n*$\n".to_owned();
+        for level in 4..=40 {
+            _ = writeln!(huge, "{level} {} 0 0 0", level - 3);
+        }
+        assert_eq!(parse(&huge).map(|_| ()), Err(Error::TooLarge));
+        // An empty root 2^62 wide parses at once, without walking its 4^59 leaves.
+        let mut empty = "[M2]\n$\n".to_owned();
+        for level in 4..=62 {
+            let child = level - 3;
+            _ = writeln!(empty, "{level} {child} {child} {child} {child}");
+        }
+        assert_eq!(parse(&empty).map(|rle| cells(&rle)), Ok(vec![]));
+        assert_eq!(
+            parse("[M2] (golly 4.0)\n").map(|rle| cells(&rle)),
+            Ok(vec![])
+        );
+    }
+}
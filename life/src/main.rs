@@ -1,6 +1,7 @@
-use wasm_bindgen::JsValue;
-
+#[cfg(target_arch = "wasm32")]
 fn main() {
+    use wasm_bindgen::JsValue;
+
     console_error_panic_hook::set_once();
 
     if let Err(err) = layout::immortalize("Conway's Game of<br />🦋 Life 🐛", |system| {
@@ -9,3 +10,9 @@ fn main() {
         web_sys::console::error_1(&err.into());
     }
 }
+
+/// Natively, there's no page to show, so run patterns from the command line.
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> std::process::ExitCode {
+    life::cli::main()
+}
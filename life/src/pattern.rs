@@ -0,0 +1,141 @@
+use system::SizeU32;
+
+use crate::universe::{Bounds, Cell, Point};
+
+/// A finite arrangement of cells, relative to its top left corner.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pattern {
+    /// Cells that aren't dead, sorted by row, then column.
+    cells: Vec<(Point, Cell)>,
+    size: SizeU32,
+}
+
+impl Pattern {
+    /// Returns a pattern of the specified cells, whose positions must not be
+    /// negative. The size is enlarged as necessary to hold every cell. Dead
+    /// cells are ignored.
+    pub fn new(mut cells: Vec<(Point, Cell)>, mut size: SizeU32) -> Pattern {
+        cells.retain(|&(_, c)| c != Cell::DEAD);
+        cells.sort_by_key(|(p, _)| (p.i, p.j));
+        cells.dedup_by_key(|(p, _)| *p);
+        for (p, _) in &cells {
+            size.height = size.height.max(p.i.cast_unsigned() + 1);
+            size.width = size.width.max(p.j.cast_unsigned() + 1);
+        }
+        Pattern { cells, size }
+    }
+
+    /// Returns the smallest pattern holding the specified cells, which may be
+    /// anywhere, and the position of its top left corner.
+    pub fn enclosing(cells: impl IntoIterator<Item = (Point, Cell)>) -> (Pattern, Point) {
+        let cells: Vec<(Point, Cell)> = cells.into_iter().collect();
+        let min = cells
+            .iter()
+            .map(|&(p, _)| p)
+            .reduce(|a, b| Point {
+                i: a.i.min(b.i),
+                j: a.j.min(b.j),
+            })
+            .unwrap_or_default();
+        let cells = cells.into_iter().map(|(p, c)| {
+            let p = Point {
+                i: p.i - min.i,
+                j: p.j - min.j,
+            };
+            (p, c)
+        });
+        (Pattern::new(cells.collect(), SizeU32::default()), min)
+    }
+
+    /// Parses the [plaintext] format, in which `O` or `*` marks a live cell and
+    /// any other character a dead one. Lines beginning with `!` are comments.
+    ///
+    /// [plaintext]: https://conwaylife.com/wiki/Plaintext
+    pub fn from_plaintext(text: &str) -> Pattern {
+        let mut cells = Vec::new();
+        let mut size = SizeU32::default();
+        let lines = text.lines().filter(|line| !line.starts_with('!'));
+        for (i, line) in (0..).zip(lines) {
+            size.height = i + 1;
+            for (j, c) in (0..).zip(line.trim_end().chars()) {
+                size.width = size.width.max(j + 1);
+                if c == 'O' || c == '*' {
+                    let p = Point {
+                        i: i.cast_signed(),
+                        j: j.cast_signed(),
+                    };
+                    cells.push((p, Cell::LIVE));
+                }
+            }
+        }
+        Pattern::new(cells, size)
+    }
+
+    pub fn states(&self) -> impl Iterator<Item = (Point, Cell)> {
+        self.cells.iter().copied()
+    }
+
+    /// Returns the highest state of any cell.
+    pub fn max_state(&self) -> Cell {
+        self.cells
+            .iter()
+            .map(|&(_, c)| c)
+            .max_by_key(|c| c.0)
+            .unwrap_or_default()
+    }
+
+    pub fn size(&self) -> SizeU32 {
+        self.size
+    }
+
+    /// Returns the pattern turned a quarter turn clockwise.
+    pub fn rotated(&self) -> Pattern {
+        let height = self.size.height.cast_signed();
+        let cells = self.cells.iter().map(|&(p, c)| {
+            let p = Point {
+                i: p.j,
+                j: height - 1 - p.i,
+            };
+            (p, c)
+        });
+        let size = SizeU32 {
+            width: self.size.height,
+            height: self.size.width,
+        };
+        Pattern::new(cells.collect(), size)
+    }
+
+    /// Returns the pattern mirrored left to right.
+    pub fn flipped(&self) -> Pattern {
+        let width = self.size.width.cast_signed();
+        let cells = self.cells.iter().map(|&(p, c)| {
+            let p = Point {
+                i: p.i,
+                j: width - 1 - p.j,
+            };
+            (p, c)
+        });
+        Pattern::new(cells.collect(), self.size)
+    }
+
+    /// Returns where the top left corner of the pattern goes to center it on
+    /// the specified point.
+    pub fn corner_for(&self, center: Point) -> Point {
+        Point {
+            i: center.i - (self.size.height / 2).cast_signed(),
+            j: center.j - (self.size.width / 2).cast_signed(),
+        }
+    }
+
+    /// Returns the cells covered by the pattern when its top left corner is at
+    /// the specified point.
+    pub fn bounds_at(&self, at: Point) -> Bounds {
+        Bounds {
+            min: at,
+            max: Point {
+                i: at.i + self.size.height.cast_signed() - 1,
+                j: at.j + self.size.width.cast_signed() - 1,
+            },
+        }
+    }
+}
@@ -0,0 +1,346 @@
+//! The [run length encoded] pattern format.
+//!
+//! [run length encoded]: https://conwaylife.com/wiki/Run_Length_Encoded
+
+use std::fmt;
+
+use system::{SizeU32, u32_to_usize};
+
+use crate::pattern::Pattern;
+use crate::universe::{Cell, Point};
+
+/// The longest line written, per the format's recommendation.
+const LINE_LEN: usize = 70;
+
+/// The most cells read from a pattern. A short run count can describe far more
+/// cells than the universe can hold.
+const MAX_CELLS: usize = 1 << 24;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The header line was not of the form `x = m, y = n`.
+    Header(String),
+    /// A run count was too large.
+    Count,
+    /// A character had no meaning in the pattern body.
+    Unexpected(char),
+    /// The pattern held more cells than the universe can.
+    TooLarge,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Header(line) => write!(f, "bad RLE header: {line}"),
+            Error::Count => write!(f, "RLE run count is too large"),
+            Error::Unexpected(c) => write!(f, "unexpected character in RLE: {c:?}"),
+            Error::TooLarge => write!(f, "RLE pattern is too large"),
+        }
+    }
+}
+
+/// A pattern with the metadata that RLE can carry.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Rle {
+    pub pattern: Pattern,
+    /// The rule from the header, such as `B3/S23`, if any.
+    pub rule: Option<String>,
+    /// Where the top left corner of the pattern belongs in the universe, per
+    /// Golly's `#CXRLE` extension.
+    pub origin: Point,
+    /// The generation of the pattern, also per `#CXRLE`.
+    pub generation: u32,
+}
+
+/// Parses the `x = m, y = n, rule = r` header line. Returns the size and rule.
+fn parse_header(line: &str) -> Result<(SizeU32, Option<String>), Error> {
+    let mut size = SizeU32::default();
+    // The rule is last, and may itself hold a comma, as in Golly's `:T64,48`.
+    let (fields, rule) = match line.split_once("rule") {
+        Some((fields, rule)) => {
+            let rule = rule.trim_start().strip_prefix('=');
+            let rule = rule.ok_or_else(|| Error::Header(line.to_owned()))?;
+            (
+                fields.trim_end().trim_end_matches(','),
+                Some(rule.trim().to_owned()),
+            )
+        }
+        None => (line, None),
+    };
+    for field in fields.split(',') {
+        let Some((key, value)) = field.split_once('=') else {
+            return Err(Error::Header(line.to_owned()));
+        };
+        let number = || {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| Error::Header(line.to_owned()))
+        };
+        match key.trim() {
+            "x" => size.width = number()?,
+            "y" => size.height = number()?,
+            _ => return Err(Error::Header(line.to_owned())),
+        }
+    }
+    Ok((size, rule))
+}
+
+/// Reads the position and generation from the fields of a `#CXRLE` line, such
+/// as `Pos=-3,7 Gen=120`. Malformed fields are ignored, like other comments.
+fn parse_cxrle(fields: &str, rle: &mut Rle) {
+    for field in fields.split_whitespace() {
+        if let Some(pos) = field.strip_prefix("Pos=") {
+            let mut xy = pos.split(',').map(str::parse);
+            if let (Some(Ok(j)), Some(Ok(i))) = (xy.next(), xy.next()) {
+                rle.origin = Point { i, j };
+            }
+        } else if let Some(generation) = field.strip_prefix("Gen=") {
+            rle.generation = generation.parse().unwrap_or_default();
+        }
+    }
+}
+
+/// Parses an RLE file. The header is optional. Multi-state patterns use `.` for
+/// dead cells and `A` through `X` for states 1 through 24, optionally preceded
+/// by `p` through `y` for each further 24 states. In two-state patterns, any
+/// lowercase letter other than `b` is a live cell.
+///
+/// # Errors
+///
+/// Will return [`Err`] if the header or body is malformed, or holds more cells
+/// than the universe can.
+pub fn parse(text: &str) -> Result<Rle, Error> {
+    let mut rle = Rle::default();
+    let mut size = None;
+    let mut body = String::new();
+    for line in text.lines().map(str::trim) {
+        if let Some(comment) = line.strip_prefix('#') {
+            if let Some(fields) = comment.strip_prefix("CXRLE") {
+                parse_cxrle(fields, &mut rle);
+            }
+        } else if size.is_none() && body.is_empty() && line.starts_with('x') {
+            let header_size;
+            (header_size, rle.rule) = parse_header(line)?;
+            size = Some(header_size);
+        } else {
+            body.push_str(line);
+        }
+    }
+
+    let mut cells = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let mut count: Option<u32> = None;
+    // The multiple of 24 added to the next state, per its prefix letter.
+    let mut prefix = None;
+    for c in body.chars() {
+        if let Some(digit) = c.to_digit(10) {
+            let n = count.unwrap_or_default();
+            count = Some(n.checked_mul(10).ok_or(Error::Count)? + digit);
+            continue;
+        }
+        if prefix.is_none() && ('p'..='y').contains(&c) {
+            prefix = Some(u32::from(c) - u32::from('p') + 1);
+            continue;
+        }
+        let state = match (prefix.take(), c) {
+            (prefix, 'A'..='X') => {
+                let state = prefix.unwrap_or_default() * 24 + u32::from(c) - u32::from('@');
+                Some(Cell(u8::try_from(state).map_err(|_| Error::Unexpected(c))?))
+            }
+            (Some(_), c) => return Err(Error::Unexpected(c)),
+            (None, 'b') => None,
+            (None, c) if c.is_ascii_lowercase() => Some(Cell::LIVE),
+            _ => None,
+        };
+        let run = count.take().unwrap_or(1);
+        let n = i32::try_from(run).map_err(|_| Error::Count)?;
+        let advance = |k: i32| k.checked_add(n).ok_or(Error::Count);
+        match (c, state) {
+            (_, Some(state)) => {
+                if cells.len().saturating_add(u32_to_usize(run)) > MAX_CELLS {
+                    return Err(Error::TooLarge);
+                }
+                let end = advance(j)?;
+                cells.extend((j..end).map(|j| (Point { i, j }, state)));
+                j = end;
+            }
+            ('!', _) => break,
+            ('$', _) => (i, j) = (advance(i)?, 0),
+            ('b' | '.', _) => j = advance(j)?,
+            (c, _) if c.is_whitespace() => (),
+            (c, _) => return Err(Error::Unexpected(c)),
+        }
+    }
+    rle.pattern = Pattern::new(cells, size.unwrap_or_default());
+    Ok(rle)
+}
+
+/// Appends runs to lines no longer than [`LINE_LEN`].
+struct Writer<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    line: usize,
+}
+
+impl Writer<'_, '_> {
+    fn push(&mut self, count: u32, tag: &str) -> fmt::Result {
+        let run = match count {
+            0 => return Ok(()),
+            1 => tag.to_string(),
+            n => format!("{n}{tag}"),
+        };
+        if self.line + run.len() > LINE_LEN {
+            self.f.write_str("\n")?;
+            self.line = 0;
+        }
+        self.line += run.len();
+        self.f.write_str(&run)
+    }
+}
+
+/// Returns the letters for a state other than dead.
+fn tag(c: Cell, multistate: bool) -> String {
+    if !multistate {
+        return "o".to_owned();
+    }
+    let (prefix, letter) = ((c.0 - 1) / 24, (c.0 - 1) % 24);
+    let letter = char::from(b'A' + letter);
+    match prefix {
+        0 => letter.to_string(),
+        _ => format!("{}{letter}", char::from(b'o' + prefix)),
+    }
+}
+
+impl fmt::Display for Rle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Rle {
+            pattern,
+            rule,
+            origin,
+            generation,
+        } = self;
+        if *origin != Point::default() || *generation != 0 {
+            writeln!(f, "#CXRLE Pos={},{} Gen={generation}", origin.j, origin.i)?;
+        }
+        let SizeU32 { width, height } = pattern.size();
+        write!(f, "x = {width}, y = {height}")?;
+        if let Some(rule) = rule {
+            write!(f, ", rule = {rule}")?;
+        }
+        writeln!(f)?;
+        let multistate = pattern.max_state().0 > 1;
+        let dead = if multistate { "." } else { "b" };
+        let mut writer = Writer { f, line: 0 };
+        // The start of the current run of cells, their state, and the length of
+        // the run so far.
+        let (mut row, mut column) = (0, 0);
+        let (mut state, mut len) = (Cell::DEAD, 0u32);
+        // Cells are in order, so each run ends at a gap, a change of state, or
+        // the end of a row.
+        for (p, c) in pattern.states() {
+            if p.i != row || p.j != column + len.cast_signed() || c != state {
+                if len > 0 {
+                    writer.push(len, &tag(state, multistate))?;
+                }
+                column += len.cast_signed();
+                len = 0;
+                if p.i != row {
+                    writer.push(p.i.abs_diff(row), "$")?;
+                    (row, column) = (p.i, 0);
+                }
+                writer.push(p.j.abs_diff(column), dead)?;
+                column = p.j;
+                state = c;
+            }
+            len += 1;
+        }
+        writer.push(len, &tag(state, multistate))?;
+        writeln!(f, "!")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(pattern: &Pattern) -> Vec<(i32, i32)> {
+        pattern.states().map(|(p, _)| (p.i, p.j)).collect()
+    }
+
+    #[test]
+    fn parses_headers_comments_and_runs() {
+        let text = "#N Glider\n#C A comment.\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!";
+        let glider = parse(text).unwrap();
+        assert_eq!(
+            cells(&glider.pattern),
+            [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]
+        );
+        assert_eq!(glider.rule.as_deref(), Some("B3/S23"));
+        let gappy = parse("x = 16, y = 5\nbo11b2o3$3obo$\n15bo!").unwrap();
+        assert_eq!(
+            cells(&gappy.pattern),
+            [
+                (0, 1),
+                (0, 13),
+                (0, 14),
+                (3, 0),
+                (3, 1),
+                (3, 2),
+                (3, 4),
+                (4, 15)
+            ]
+        );
+        assert!(matches!(parse("x = 3, y = ?\no!"), Err(Error::Header(_))));
+        assert_eq!(parse("3o%").err(), Some(Error::Unexpected('%')));
+        assert_eq!(parse("99999999999o!").err(), Some(Error::Count));
+    }
+
+    #[test]
+    fn rejects_oversized_runs() {
+        let text = "x = 1, y = 1\n2000000000o!";
+        assert_eq!(parse(text).err(), Some(Error::TooLarge));
+        // Dead runs hold no cells, so they may be long.
+        assert!(parse("2000000000b$o!").is_ok());
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = "x = 16, y = 5, rule = B36/S23\nbo11b2o3$3obo$15bo!\n";
+        let rle = parse(text).unwrap();
+        assert_eq!(rle.to_string(), text);
+        let moved = Rle {
+            origin: Point { i: -4, j: 9 },
+            generation: 77,
+            ..rle
+        };
+        let text = moved.to_string();
+        assert!(text.starts_with("#CXRLE Pos=9,-4 Gen=77\n"));
+        assert_eq!(parse(&text), Ok(moved));
+    }
+
+    #[test]
+    fn multistate_round_trip() {
+        let text = "x = 4, y = 2, rule = WireWorld\n.A2B$2.CpF!\n";
+        let rle = parse(text).unwrap();
+        let states: Vec<_> = rle.pattern.states().map(|(p, c)| (p.i, p.j, c.0)).collect();
+        assert_eq!(
+            states,
+            [(0, 1, 1), (0, 2, 2), (0, 3, 2), (1, 2, 3), (1, 3, 30)]
+        );
+        assert_eq!(rle.to_string(), text);
+        assert_eq!(parse("pb!").err(), Some(Error::Unexpected('b')));
+        assert_eq!(parse("yX!").err(), Some(Error::Unexpected('X')));
+    }
+
+    #[test]
+    fn long_lines_are_wrapped() {
+        let row = (0..100).map(|j| (Point { i: 0, j: 2 * j }, Cell::LIVE));
+        let rle = Rle {
+            pattern: Pattern::new(row.collect(), SizeU32::default()),
+            ..Rle::default()
+        };
+        let text = rle.to_string();
+        assert!(text.lines().all(|line| line.len() <= LINE_LEN));
+        assert_eq!(parse(&text), Ok(rle));
+    }
+}
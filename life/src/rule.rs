@@ -0,0 +1,306 @@
+//! Rules in which a cell's next state depends only on its own state and the
+//! number of live cells among its neighbors.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::grid::Grid;
+use crate::ltl::{self, Ltl};
+use crate::universe::Cell;
+
+/// The most states of a Generations rule, so that each state has a letter in
+/// RLE.
+pub const MAX_STATES: u8 = 25;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The rule was not in `B3/S23`, `23/3`, `B2/S/C3`, `B2/S34H`, or Larger
+    /// than Life notation, nor a name.
+    Syntax(String),
+    /// Births from no neighbors would fill the unbounded universe at once.
+    BirthFromNothing,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Syntax(rule) => write!(f, "unrecognized rule: {rule}"),
+            Error::BirthFromNothing => write!(f, "rules with B0 are not supported"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rule {
+    /// Dead cells are born, and live cells survive, for the neighbor counts in
+    /// the respective bit masks. With more than two states, this is a
+    /// Generations rule: live cells that don't survive pass through dying
+    /// states, which are neither live nor available for births, before dying.
+    Totalistic {
+        birth: u16,
+        survival: u16,
+        states: u8,
+        grid: Grid,
+    },
+    /// Electrons, each a head followed by a tail, flowing along conductors.
+    Wireworld,
+    /// Like a totalistic rule, but over a larger neighborhood.
+    Larger(Ltl),
+}
+
+impl Rule {
+    /// Conway's Life: B3/S23.
+    pub const CONWAY: Rule = Rule::Totalistic {
+        birth: 1 << 3,
+        survival: 1 << 2 | 1 << 3,
+        states: 2,
+        grid: Grid::Square,
+    };
+
+    /// Brian's Brain: B2/S/C3. Every live cell dies, after one dying state.
+    pub const BRIANS_BRAIN: Rule = Rule::Totalistic {
+        birth: 1 << 2,
+        survival: 0,
+        states: 3,
+        grid: Grid::Square,
+    };
+
+    /// Wireworld's states, after empty.
+    pub const WIREWORLD_HEAD: Cell = Cell(1);
+    pub const WIREWORLD_TAIL: Cell = Cell(2);
+    pub const WIREWORLD_CONDUCTOR: Cell = Cell(3);
+
+    /// Returns the number of states, including dead.
+    pub fn states(self) -> u8 {
+        match self {
+            Rule::Totalistic { states, .. } => states,
+            Rule::Wireworld => 4,
+            Rule::Larger(ltl) => ltl.states(),
+        }
+    }
+
+    /// Returns the tiling whose neighbors the rule counts.
+    pub fn grid(self) -> Grid {
+        match self {
+            Rule::Totalistic { grid, .. } => grid,
+            Rule::Wireworld | Rule::Larger(_) => Grid::Square,
+        }
+    }
+
+    /// Returns the name of a state other than dead.
+    pub fn state_name(self, c: Cell) -> String {
+        match (self, c.0) {
+            (Rule::Wireworld, 1) => "Electron head".to_owned(),
+            (Rule::Wireworld, 2) => "Electron tail".to_owned(),
+            (Rule::Wireworld, _) => "Conductor".to_owned(),
+            (Rule::Totalistic { states: 3.., .. }, 2..) => format!("Dying ({})", c.0 - 1),
+            (Rule::Larger(ltl), 2..) if ltl.states() > 2 => format!("Dying ({})", c.0 - 1),
+            (Rule::Totalistic { .. } | Rule::Larger(_), _) => "Live".to_owned(),
+        }
+    }
+
+    /// Returns the next state of a cell, where `c` is its current state and
+    /// `n` its number of live neighbors. In Wireworld, electron heads count as
+    /// live.
+    pub fn next(self, c: Cell, n: u16) -> Cell {
+        match (self, c.0) {
+            (Rule::Totalistic { birth, .. }, 0) => Cell(u8::from(birth & 1 << n != 0)),
+            (Rule::Totalistic { survival, .. }, 1) if survival & 1 << n != 0 => Cell::LIVE,
+            (Rule::Totalistic { states, .. }, state) => {
+                let next = state.saturating_add(1);
+                Cell(if next < states { next } else { 0 })
+            }
+            (Rule::Wireworld, 0) => Cell::DEAD,
+            (Rule::Wireworld, 1) => Rule::WIREWORLD_TAIL,
+            (Rule::Wireworld, 3) if matches!(n, 1 | 2) => Rule::WIREWORLD_HEAD,
+            (Rule::Wireworld, _) => Rule::WIREWORLD_CONDUCTOR,
+            (Rule::Larger(ltl), _) => ltl.next(c, n),
+        }
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::CONWAY
+    }
+}
+
+/// Parses a string of distinct neighbor counts, such as `23`, as a bit mask.
+fn parse_counts(digits: &str, grid: Grid) -> Option<u16> {
+    digits.chars().try_fold(0, |mask, c| {
+        let n = c
+            .to_digit(10)
+            .filter(|&n| n <= u32::from(grid.neighbor_count()))?;
+        Some(mask | 1 << n)
+    })
+}
+
+/// Strips a letter from the start of part of a rule, in either case.
+fn strip(part: &str, prefix: char) -> Option<&str> {
+    part.strip_prefix([prefix, prefix.to_ascii_lowercase()])
+}
+
+impl FromStr for Rule {
+    type Err = Error;
+
+    /// Parses `B3/S23` notation, in either order and either case, or the older
+    /// survival-first `23/3` notation, optionally followed by a number of
+    /// states, as in `B2/S/C3` or `/2/3`, then by `H` for the hexagonal grid or
+    /// `L` for the triangular grid, as in `B2/S34H`. Also parses Larger than
+    /// Life rules, such as `R5,C0,M1,S34..58,B34..45,NM`, and the names
+    /// `Wireworld` and `BriansBrain`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let syntax = || Error::Syntax(s.to_owned());
+        let s = s.trim();
+        if strip(s, 'R').is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit())) {
+            return ltl::parse(s).map(Rule::Larger);
+        }
+        if s.eq_ignore_ascii_case("wireworld") {
+            return Ok(Rule::Wireworld);
+        }
+        if s.eq_ignore_ascii_case("briansbrain") {
+            return Ok(Rule::BRIANS_BRAIN);
+        }
+        let (s, grid) = match s.chars().next_back().and_then(Grid::from_suffix) {
+            Some(grid) => (&s[..s.len() - 1], grid),
+            None => (s, Grid::Square),
+        };
+        let mut parts = s.split('/');
+        let (Some(left), Some(right)) = (parts.next(), parts.next()) else {
+            return Err(syntax());
+        };
+        let states = match (parts.next(), parts.next()) {
+            (None, _) => 2,
+            (Some(states), None) => strip(states, 'C')
+                .unwrap_or(states)
+                .parse()
+                .ok()
+                .filter(|states| (2..=MAX_STATES).contains(states))
+                .ok_or_else(syntax)?,
+            (Some(_), Some(_)) => return Err(syntax()),
+        };
+        let (birth, survival) = match (strip(left, 'B'), strip(right, 'S')) {
+            (Some(birth), Some(survival)) => (birth, survival),
+            _ => match (strip(left, 'S'), strip(right, 'B')) {
+                (Some(survival), Some(birth)) => (birth, survival),
+                _ => (right, left),
+            },
+        };
+        let birth = parse_counts(birth, grid).ok_or_else(syntax)?;
+        if birth & 1 != 0 {
+            return Err(Error::BirthFromNothing);
+        }
+        Ok(Rule::Totalistic {
+            birth,
+            survival: parse_counts(survival, grid).ok_or_else(syntax)?,
+            states,
+            grid,
+        })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |mask: u16| -> String {
+            (0..=9)
+                .filter(|n| mask & 1 << n != 0)
+                .map(|n| char::from(b'0' + n))
+                .collect()
+        };
+        match *self {
+            Rule::Totalistic {
+                birth,
+                survival,
+                states,
+                grid,
+            } => {
+                write!(f, "B{}/S{}", counts(birth), counts(survival))?;
+                if states > 2 {
+                    write!(f, "/C{states}")?;
+                }
+                if let Some(suffix) = grid.suffix() {
+                    write!(f, "{suffix}")?;
+                }
+                Ok(())
+            }
+            // As spelled by Golly.
+            Rule::Wireworld => write!(f, "WireWorld"),
+            Rule::Larger(ltl) => ltl.fmt(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notations() {
+        assert_eq!("B3/S23".parse(), Ok(Rule::CONWAY));
+        assert_eq!("b3/s23".parse(), Ok(Rule::CONWAY));
+        assert_eq!("S23/B3".parse(), Ok(Rule::CONWAY));
+        assert_eq!("23/3".parse(), Ok(Rule::CONWAY));
+        let highlife: Rule = "B36/S23".parse().unwrap();
+        assert_eq!(highlife.to_string(), "B36/S23");
+        assert_eq!(highlife.next(Cell::DEAD, 6), Cell::LIVE);
+        assert_eq!(Rule::CONWAY.next(Cell::DEAD, 6), Cell::DEAD);
+        assert_eq!(
+            "B/S"
+                .parse::<Rule>()
+                .map(|rule| rule.to_string())
+                .as_deref(),
+            Ok("B/S")
+        );
+        assert_eq!("B0/S8".parse::<Rule>(), Err(Error::BirthFromNothing));
+        assert!(matches!("B9/S".parse::<Rule>(), Err(Error::Syntax(_))));
+        assert!(matches!("Life".parse::<Rule>(), Err(Error::Syntax(_))));
+    }
+
+    #[test]
+    fn other_grids() {
+        let hex: Rule = "B2/S34H".parse().unwrap();
+        assert_eq!(hex.grid(), Grid::Hexagonal);
+        assert_eq!(hex.to_string(), "B2/S34H");
+        assert_eq!("b2/s34h".parse(), Ok(hex));
+        let triangular: Rule = "B45/S34/C3L".parse().unwrap();
+        assert_eq!(triangular.grid(), Grid::Triangular);
+        assert_eq!(triangular.states(), 3);
+        assert_eq!(triangular.to_string(), "B45/S34/C3L");
+        assert_eq!(triangular.next(Cell::LIVE, 12), Cell(2));
+        // Hexagonal cells have only six neighbors.
+        assert!(matches!("B7/S34H".parse::<Rule>(), Err(Error::Syntax(_))));
+        assert_eq!(Rule::CONWAY.grid(), Grid::Square);
+    }
+
+    #[test]
+    fn generations_and_wireworld() {
+        assert_eq!("B2/S/C3".parse(), Ok(Rule::BRIANS_BRAIN));
+        assert_eq!("/2/3".parse(), Ok(Rule::BRIANS_BRAIN));
+        assert_eq!("BriansBrain".parse(), Ok(Rule::BRIANS_BRAIN));
+        assert_eq!(Rule::BRIANS_BRAIN.to_string(), "B2/S/C3");
+        let star_wars: Rule = "B2/S345/C4".parse().unwrap();
+        assert_eq!(star_wars.to_string(), "B2/S345/C4");
+        assert!(matches!("B2/S/C26".parse::<Rule>(), Err(Error::Syntax(_))));
+        // Live cells that don't survive pass through each dying state.
+        let (live, dying) = (Cell::LIVE, Cell(2));
+        assert_eq!(star_wars.next(live, 4), live);
+        assert_eq!(star_wars.next(live, 2), dying);
+        assert_eq!(star_wars.next(dying, 2), Cell(3));
+        assert_eq!(star_wars.next(Cell(3), 2), Cell::DEAD);
+        assert_eq!(star_wars.next(Cell::DEAD, 2), live);
+
+        assert_eq!("wireworld".parse(), Ok(Rule::Wireworld));
+        assert_eq!(Rule::Wireworld.to_string(), "WireWorld");
+        let (head, tail, wire) = (
+            Rule::WIREWORLD_HEAD,
+            Rule::WIREWORLD_TAIL,
+            Rule::WIREWORLD_CONDUCTOR,
+        );
+        assert_eq!(Rule::Wireworld.next(head, 0), tail);
+        assert_eq!(Rule::Wireworld.next(tail, 1), wire);
+        assert_eq!(Rule::Wireworld.next(wire, 1), head);
+        assert_eq!(Rule::Wireworld.next(wire, 2), head);
+        assert_eq!(Rule::Wireworld.next(wire, 3), wire);
+        assert_eq!(Rule::Wireworld.next(Cell::DEAD, 2), Cell::DEAD);
+    }
+}
@@ -0,0 +1,294 @@
+//! A census of the objects left by many random soups, in the manner of
+//! apgsearch: each soup runs until it settles, then what remains is classified
+//! and tallied.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::catalog::Category;
+use crate::census::{Classifier, Island};
+use crate::soup::Soup;
+use crate::stats::Stats;
+use crate::universe::{Bounds, Cell, Turnover, Universe};
+
+/// Generations a soup may run before it's given up on.
+pub const DEFAULT_LIMIT: u32 = 10_000;
+
+/// Generations between searches for spaceships that have escaped.
+const ESCAPE_INTERVAL: u32 = 64;
+
+/// How many cells beyond the bounds of everything else a spaceship must be to
+/// count as escaped.
+const ESCAPE_MARGIN: i32 = 32;
+
+/// Objects found in fewer soups than one in this many are flagged as rare.
+const RARE_ONE_IN: u64 = 100;
+
+/// Returns whether the bounds are more than [`ESCAPE_MARGIN`] apart.
+fn far_apart(a: Bounds, b: Bounds) -> bool {
+    let gap = (a.min.i - b.max.i)
+        .max(b.min.i - a.max.i)
+        .max(a.min.j - b.max.j)
+        .max(b.min.j - a.max.j);
+    gap > ESCAPE_MARGIN
+}
+
+/// Removes the spaceships far from everything else, which will never meet it
+/// again, and returns them. Spaceships may fly alongside one another, so only
+/// the distance to other objects counts.
+fn remove_escaped(universe: &mut Universe, classifier: &Classifier) -> Vec<Island> {
+    let (spaceships, rest): (Vec<Island>, Vec<Island>) = classifier
+        .classify(universe)
+        .into_iter()
+        .partition(|island| island.object.map(|entry| entry.category) == Some(Category::Spaceship));
+    let rest = Bounds::enclosing(rest.iter().flat_map(|island| island.cells.iter().copied()));
+    let escaped: Vec<Island> = spaceships
+        .into_iter()
+        .filter(|island| {
+            let bounds = Bounds::enclosing(island.cells.iter().copied());
+            match (bounds, rest) {
+                (Some(bounds), Some(rest)) => far_apart(bounds, rest),
+                (_, None) => true,
+                (None, _) => false,
+            }
+        })
+        .collect();
+    for p in escaped.iter().flat_map(|island| &island.cells) {
+        universe.set(*p, Cell::DEAD);
+    }
+    escaped
+}
+
+/// Runs the universe until it settles into still lifes and oscillators, and
+/// returns the generation at which it did, along with any spaceships that
+/// escaped on the way, or [`None`] if it hasn't settled by the limit.
+/// Spaceships are removed once they're far from everything else, so that
+/// soups emitting them can settle.
+pub fn stabilize(
+    universe: &mut Universe,
+    limit: u32,
+    classifier: &Classifier,
+) -> Option<(u32, Vec<Island>)> {
+    let mut stats = Stats::new();
+    let mut escaped = Vec::new();
+    stats.record(universe, Turnover::default());
+    for generation in 0..=limit {
+        if stats.settled().is_some() {
+            return Some((generation, escaped));
+        }
+        if generation > 0 && generation.is_multiple_of(ESCAPE_INTERVAL) {
+            let removed = remove_escaped(universe, classifier);
+            if !removed.is_empty() {
+                escaped.extend(removed);
+                stats.interrupt();
+            }
+        }
+        let turnover = universe.tick();
+        stats.record(universe, turnover);
+    }
+    None
+}
+
+/// How often an object was found, and where first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Tally {
+    pub count: u64,
+    /// The seed of the first soup that left the object.
+    pub first_seed: u32,
+    /// How many soups left at least one.
+    pub soups: u64,
+}
+
+/// The objects left by a run of soups, with consecutive seeds.
+pub struct Search {
+    /// The first soup; the rest differ only in seed.
+    soup: Soup,
+    limit: u32,
+    soups: u64,
+    /// Seeds of the soups that didn't settle within the limit.
+    unsettled: Vec<u32>,
+    tallies: HashMap<String, Tally>,
+    classifier: Classifier,
+}
+
+impl Search {
+    /// Prepares to run soups like the specified one, starting at its seed,
+    /// for at most `limit` generations each.
+    pub fn new(soup: Soup, limit: u32) -> Search {
+        Search {
+            soup,
+            limit,
+            soups: 0,
+            unsettled: Vec::new(),
+            tallies: HashMap::new(),
+            classifier: Classifier::new(),
+        }
+    }
+
+    /// Runs the next soup, and adds what it leaves to the census.
+    pub fn run_one(&mut self) {
+        let seed = self
+            .soup
+            .seed
+            .wrapping_add(u32::try_from(self.soups).unwrap_or_default());
+        let soup = Soup { seed, ..self.soup };
+        let mut universe = soup.generate();
+        self.soups += 1;
+        let Some((_, escaped)) = stabilize(&mut universe, self.limit, &self.classifier) else {
+            self.unsettled.push(seed);
+            return;
+        };
+        let mut names: Vec<String> = self
+            .classifier
+            .classify(&universe)
+            .iter()
+            .chain(&escaped)
+            .map(Island::name)
+            .collect();
+        names.sort_unstable();
+        for (index, name) in names.iter().enumerate() {
+            let tally = self.tallies.entry(name.clone()).or_insert(Tally {
+                count: 0,
+                first_seed: seed,
+                soups: 0,
+            });
+            tally.count += 1;
+            if index == 0 || names[index - 1] != *name {
+                tally.soups += 1;
+            }
+        }
+    }
+
+    /// Returns each object found, with its tally, most common first.
+    pub fn tallies(&self) -> Vec<(&str, Tally)> {
+        let mut tallies: Vec<_> = self
+            .tallies
+            .iter()
+            .map(|(name, &tally)| (name.as_str(), tally))
+            .collect();
+        tallies.sort_unstable_by(|(a, m), (b, n)| n.count.cmp(&m.count).then_with(|| a.cmp(b)));
+        tallies
+    }
+
+    /// Returns true if the object was left by few soups, or isn't known, and
+    /// so is worth a look.
+    fn is_notable(&self, name: &str, tally: Tally) -> bool {
+        tally.soups * RARE_ONE_IN < self.soups || name.starts_with("Unknown")
+    }
+
+    /// Describes the census as a table, flagging rare and unknown objects.
+    pub fn report(&self) -> String {
+        let Soup {
+            seed,
+            density,
+            size,
+            symmetry,
+        } = self.soup;
+        let mut report = String::new();
+        // Writing to a string can't fail.
+        _ = writeln!(
+            report,
+            "# {} soups of {size}x{size} cells at {density}% ({symmetry}), from seed {seed}",
+            self.soups
+        );
+        if let Some(first) = self.unsettled.first() {
+            _ = writeln!(
+                report,
+                "# {} unsettled after {} generations, first seed {first}",
+                self.unsettled.len(),
+                self.limit
+            );
+        }
+        let tallies = self.tallies();
+        let width = tallies
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or_default();
+        _ = writeln!(
+            report,
+            "{:width$}  {:>8}  {:>10}",
+            "Object", "Count", "First seed"
+        );
+        for (name, tally) in tallies {
+            let flag = if self.is_notable(name, tally) {
+                "  *"
+            } else {
+                ""
+            };
+            _ = writeln!(
+                report,
+                "{name:width$}  {:>8}  {:>10}{flag}",
+                tally.count, tally.first_seed
+            );
+        }
+        if self
+            .tallies
+            .iter()
+            .any(|(name, &tally)| self.is_notable(name, tally))
+        {
+            _ = writeln!(
+                report,
+                "* Rare (in under 1 soup in {RARE_ONE_IN}) or unknown"
+            );
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::CATALOG;
+    use crate::universe::Point;
+
+    fn stamp(universe: &mut Universe, name: &str, at: Point) {
+        let entry = CATALOG.iter().find(|entry| entry.name == name).unwrap();
+        universe.stamp(&entry.pattern(), at);
+    }
+
+    #[test]
+    fn stabilizes_once_settled() {
+        let classifier = Classifier::new();
+        let mut universe = Universe::new();
+        stamp(&mut universe, "Block", Point::default());
+        stamp(&mut universe, "Blinker", Point { i: 0, j: 10 });
+        let (generation, escaped) = stabilize(&mut universe, 100, &classifier).unwrap();
+        assert_eq!(generation, 2);
+        assert!(escaped.is_empty());
+        // The glider is removed once far enough away, after which the blinker
+        // repeats two generations later, as it did at first.
+        stamp(&mut universe, "Glider", Point { i: 40, j: 40 });
+        let (generation, escaped) = stabilize(&mut universe, 100, &classifier).unwrap();
+        assert_eq!(generation, ESCAPE_INTERVAL + 1 + 2);
+        let names: Vec<_> = escaped.iter().map(Island::name).collect();
+        assert_eq!(names, ["Glider"]);
+        // The R-pentomino takes 1103 generations to settle.
+        let mut universe = Universe::new();
+        stamp(&mut universe, "R-pentomino", Point::default());
+        assert!(stabilize(&mut universe, 100, &classifier).is_none());
+    }
+
+    #[test]
+    fn tallies_what_soups_leave() {
+        let soup = Soup {
+            seed: 1,
+            size: 16,
+            ..Soup::default()
+        };
+        let mut search = Search::new(soup, DEFAULT_LIMIT);
+        for _ in 0..2 {
+            search.run_one();
+        }
+        let tallies = search.tallies();
+        assert!(!tallies.is_empty());
+        for (name, tally) in &tallies {
+            assert!(!name.starts_with("Unknown"), "{name}");
+            assert!((1..=2).contains(&tally.soups));
+            assert!(tally.count >= tally.soups);
+            assert!((1..=2).contains(&tally.first_seed));
+        }
+        let report = search.report();
+        assert!(report.starts_with("# 2 soups of 16x16 cells at 50% (C1), from seed 1\n"));
+    }
+}
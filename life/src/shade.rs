@@ -0,0 +1,167 @@
+//! Ways of coloring cells, beyond plain live and dead.
+
+use std::fmt;
+
+use system::{f64_to_u32_saturating, u32_to_usize, usize_to_u32};
+
+use crate::activity::TRAIL_LEN;
+use crate::rule::Rule;
+
+/// What the colors on the canvas mean.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RenderMode {
+    /// Every live cell is the same color.
+    #[default]
+    Plain,
+    /// Live cells are colored by the number of generations they've survived.
+    Age,
+    /// Cells that died recently leave fading trails.
+    Trails,
+    /// Cells are colored by how often they've changed state, since this mode
+    /// was selected.
+    Heat,
+}
+
+impl RenderMode {
+    pub const ALL: [RenderMode; 4] = [
+        RenderMode::Plain,
+        RenderMode::Age,
+        RenderMode::Trails,
+        RenderMode::Heat,
+    ];
+
+    /// Returns true if the mode needs each generation's changes recorded.
+    pub fn tracks_activity(self) -> bool {
+        matches!(self, RenderMode::Trails | RenderMode::Heat)
+    }
+}
+
+impl fmt::Display for RenderMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RenderMode::Plain => "Plain",
+            RenderMode::Age => "Age",
+            RenderMode::Trails => "Trails",
+            RenderMode::Heat => "Heat map",
+        })
+    }
+}
+
+/// The number of distinct colors per mode. Cells are drawn in batches by
+/// color, since changing the fill style is slow.
+pub const SHADES: usize = 8;
+
+/// From newborn yellow to ancient blue.
+pub const AGE_COLORS: [&str; SHADES] = [
+    "hsl(50, 90%, 55%)",
+    "hsl(80, 60%, 50%)",
+    "hsl(110, 40%, 45%)",
+    "hsl(145, 19%, 45%)",
+    "hsl(170, 30%, 40%)",
+    "hsl(195, 40%, 40%)",
+    "hsl(215, 45%, 40%)",
+    "hsl(235, 50%, 35%)",
+];
+
+/// From freshly dead to nearly faded.
+pub const TRAIL_COLORS: [&str; SHADES] = [
+    "hsl(145, 19%, 60%)",
+    "hsl(145, 19%, 65%)",
+    "hsl(145, 19%, 70%)",
+    "hsl(145, 19%, 75%)",
+    "hsl(145, 19%, 80%)",
+    "hsl(145, 19%, 85%)",
+    "hsl(145, 19%, 89%)",
+    "hsl(145, 19%, 93%)",
+];
+
+/// From cold blue to hot red.
+pub const HEAT_COLORS: [&str; SHADES] = [
+    "hsl(240, 60%, 75%)",
+    "hsl(210, 60%, 65%)",
+    "hsl(180, 60%, 50%)",
+    "hsl(120, 55%, 50%)",
+    "hsl(60, 80%, 50%)",
+    "hsl(40, 90%, 50%)",
+    "hsl(20, 90%, 50%)",
+    "hsl(0, 90%, 45%)",
+];
+
+/// Returns the color of each state but dead, in order, for the specified rule.
+/// Dying states fade toward the background.
+pub fn state_colors(rule: Rule) -> Vec<String> {
+    match rule {
+        Rule::Wireworld => [
+            "hsl(210, 90%, 50%)", // Blue electron head.
+            "hsl(0, 80%, 55%)",   // Red electron tail.
+            "hsl(40, 90%, 50%)",  // Amber conductor.
+        ]
+        .map(str::to_owned)
+        .to_vec(),
+        Rule::BRIANS_BRAIN => [
+            "hsl(265, 50%, 45%)", // Firing violet.
+            "hsl(265, 50%, 80%)", // Refractory lilac.
+        ]
+        .map(str::to_owned)
+        .to_vec(),
+        Rule::Totalistic { .. } | Rule::Larger(_) => {
+            let dying = u32::from(rule.states() - 1);
+            (0..dying)
+                .map(|k| format!("hsl(145, 19%, {}%)", 45 + k * 45 / dying))
+                .collect()
+        }
+    }
+}
+
+/// Returns the index into [`AGE_COLORS`] for a cell of the specified age. Each
+/// shade spans twice as many generations as the last.
+pub fn age_shade(age: u16) -> usize {
+    u32_to_usize(u16::BITS - age.leading_zeros()).min(SHADES - 1)
+}
+
+/// Returns the index into [`TRAIL_COLORS`] for a trail of the specified age.
+pub fn trail_shade(age: u8) -> usize {
+    usize::from(age) * SHADES / usize::from(TRAIL_LEN)
+}
+
+/// Returns the index into [`HEAT_COLORS`] for a cell that has changed `heat`
+/// times, on a logarithmic scale relative to the hottest cell.
+pub fn heat_shade(heat: u32, hottest: u32) -> usize {
+    let scale = f64::from(heat).ln_1p() / f64::from(hottest.max(1)).ln_1p();
+    let shade = f64_to_u32_saturating(scale * f64::from(usize_to_u32(SHADES)));
+    u32_to_usize(shade).min(SHADES - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shades_cover_their_ranges() {
+        assert_eq!(age_shade(0), 0);
+        assert_eq!(age_shade(1), 1);
+        assert_eq!(age_shade(3), 2);
+        assert_eq!(age_shade(u16::MAX), SHADES - 1);
+        assert_eq!(trail_shade(0), 0);
+        assert_eq!(trail_shade(TRAIL_LEN - 1), SHADES - 1);
+        assert_eq!(heat_shade(0, 100), 0);
+        assert_eq!(heat_shade(100, 100), SHADES - 1);
+        assert!(heat_shade(10, 100) < heat_shade(50, 100));
+    }
+
+    #[test]
+    fn every_state_has_a_color() {
+        assert_eq!(state_colors(Rule::CONWAY), ["hsl(145, 19%, 45%)"]);
+        assert_eq!(state_colors(Rule::Wireworld).len(), 3);
+        assert_eq!(state_colors(Rule::BRIANS_BRAIN).len(), 2);
+        let star_wars = "B2/S345/C4".parse().unwrap();
+        assert_eq!(
+            state_colors(star_wars),
+            [
+                "hsl(145, 19%, 45%)",
+                "hsl(145, 19%, 60%)",
+                "hsl(145, 19%, 75%)"
+            ]
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! Links that recreate the universe, by encoding it in the URL fragment as RLE,
+//! then base64url.
+
+use std::fmt;
+
+use crate::base64;
+use crate::pattern::Pattern;
+use crate::rle::{self, Rle};
+use crate::rule::{self, Rule};
+use crate::universe::{Topology, Universe};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The fragment was not valid base64url, or did not decode to text.
+    Encoding,
+    Rle(rle::Error),
+    Rule(rule::Error),
+    /// The topology after the rule was not `T` and a width and height.
+    Topology(String),
+    /// A cell was in a state the rule doesn't have.
+    State(u8),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Encoding => write!(f, "the link is damaged"),
+            Error::Rle(e) => e.fmt(f),
+            Error::Rule(e) => e.fmt(f),
+            Error::Topology(topology) => write!(f, "unsupported topology: {topology}"),
+            Error::State(state) => write!(f, "the rule has no cell state {state}"),
+        }
+    }
+}
+
+impl From<rle::Error> for Error {
+    fn from(value: rle::Error) -> Self {
+        Error::Rle(value)
+    }
+}
+
+impl From<rule::Error> for Error {
+    fn from(value: rule::Error) -> Self {
+        Error::Rule(value)
+    }
+}
+
+/// Returns the live cells of the universe as RLE, with the rule, topology,
+/// position, and generation.
+pub fn to_rle(universe: &Universe, generation: u32) -> Rle {
+    let (pattern, origin) = Pattern::enclosing(universe.states());
+    let rule = match universe.topology() {
+        Topology::Plane => universe.rule().to_string(),
+        topology @ Topology::Torus(_) => format!("{}:{topology}", universe.rule()),
+    };
+    Rle {
+        pattern,
+        rule: Some(rule),
+        origin,
+        generation,
+    }
+}
+
+/// Returns a URL fragment, without the leading `#`, from which
+/// [`from_fragment`] recreates the universe at the specified generation.
+pub fn to_fragment(universe: &Universe, generation: u32) -> String {
+    base64::encode(to_rle(universe, generation).to_string().as_bytes())
+}
+
+/// Parses a rule with an optional topology suffix, such as `B3/S23:T64,48`.
+///
+/// # Errors
+///
+/// Will return [`Err`] if either part is malformed.
+pub fn parse_rule(text: &str) -> Result<(Rule, Topology), Error> {
+    let (rule, topology) = text.split_once(':').unwrap_or((text, ""));
+    let topology = topology
+        .parse()
+        .map_err(|()| Error::Topology(topology.to_owned()))?;
+    Ok((rule.parse()?, topology))
+}
+
+/// Checks that every cell of the pattern is in a state the rule has.
+///
+/// # Errors
+///
+/// Will return [`Err`] with the highest state if the rule doesn't have it.
+pub fn check_states(pattern: &Pattern, rule: Rule) -> Result<(), Error> {
+    let state = pattern.max_state();
+    if state.0 < rule.states() {
+        Ok(())
+    } else {
+        Err(Error::State(state.0))
+    }
+}
+
+/// Recreates a universe from a URL fragment made by [`to_fragment`], without
+/// the leading `#`. Also returns the generation.
+///
+/// # Errors
+///
+/// Will return [`Err`] if the fragment is malformed.
+pub fn from_fragment(fragment: &str) -> Result<(Universe, u32), Error> {
+    let bytes = base64::decode(fragment).ok_or(Error::Encoding)?;
+    let text = String::from_utf8(bytes).map_err(|_| Error::Encoding)?;
+    from_rle(rle::parse(&text)?)
+}
+
+/// Recreates a universe from RLE, such as that made by [`to_rle`]. Also
+/// returns the generation.
+///
+/// # Errors
+///
+/// Will return [`Err`] if the rule or topology is malformed, or if cells are in
+/// states the rule doesn't have.
+pub fn from_rle(
+    Rle {
+        pattern,
+        rule,
+        origin,
+        generation,
+    }: Rle,
+) -> Result<(Universe, u32), Error> {
+    let (rule, topology) = match rule {
+        Some(rule) => parse_rule(&rule)?,
+        None => (Rule::default(), Topology::Plane),
+    };
+    check_states(&pattern, rule)?;
+    let mut universe = Universe::new();
+    universe.set_rule(rule);
+    universe.set_topology(topology);
+    universe.stamp(&pattern, origin);
+    Ok((universe, generation))
+}
+
+#[cfg(test)]
+mod tests {
+    use system::SizeU32;
+
+    use super::*;
+    use crate::soup::Soup;
+    use crate::universe::Point;
+
+    #[test]
+    fn round_trip() {
+        let soup = Soup {
+            seed: 5,
+            size: 40,
+            ..Soup::default()
+        };
+        let mut universe = soup.generate();
+        universe.set_rule("B36/S23".parse().unwrap());
+        for _ in 0..10 {
+            universe.tick();
+        }
+        let fragment = to_fragment(&universe, 10);
+        assert!(
+            fragment
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b"-_".contains(&b))
+        );
+        let (copy, generation) = from_fragment(&fragment).unwrap();
+        assert_eq!(generation, 10);
+        assert_eq!(copy.rule(), universe.rule());
+        assert_eq!(copy.topology(), Topology::Plane);
+        assert_eq!(copy.sorted_cells(), universe.sorted_cells());
+    }
+
+    #[test]
+    fn round_trip_on_a_torus() {
+        let mut universe = Universe::new();
+        universe.set_topology(Topology::Torus(SizeU32 {
+            width: 20,
+            height: 10,
+        }));
+        universe.set(Point { i: 9, j: 19 }, crate::universe::Cell::LIVE);
+        let (copy, generation) = from_fragment(&to_fragment(&universe, 0)).unwrap();
+        assert_eq!(generation, 0);
+        assert_eq!(copy.topology(), universe.topology());
+        assert_eq!(copy.sorted_cells(), [Point { i: 9, j: 19 }]);
+    }
+
+    #[test]
+    fn damaged_links() {
+        assert_eq!(from_fragment("!!!").err(), Some(Error::Encoding));
+        let bad_rule = base64::encode(b"x = 1, y = 1, rule = B0/S\no!");
+        assert_eq!(
+            from_fragment(&bad_rule).err(),
+            Some(Error::Rule(rule::Error::BirthFromNothing))
+        );
+        let bad_topology = base64::encode(b"x = 1, y = 1, rule = B3/S23:K9\no!");
+        assert!(matches!(
+            from_fragment(&bad_topology),
+            Err(Error::Topology(_))
+        ));
+        let bad_state = base64::encode(b"x = 3, y = 1, rule = B3/S23\nACA!");
+        assert_eq!(from_fragment(&bad_state).err(), Some(Error::State(3)));
+    }
+}
@@ -0,0 +1,233 @@
+//! Random initial patterns, called soups, which can be reproduced from a seed.
+
+use std::fmt;
+use std::str::FromStr;
+
+use math::LinearCongruentialGenerator;
+use system::u32_to_usize;
+
+use crate::universe::{Bounds, Cell, Point, Universe};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Symmetry {
+    #[default]
+    None,
+    /// Unchanged by a half turn.
+    C2,
+    /// Unchanged by a quarter turn.
+    C4,
+    /// Unchanged by any quarter turn or reflection.
+    D8,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 4] = [Symmetry::None, Symmetry::C2, Symmetry::C4, Symmetry::D8];
+
+    /// Returns the cells to which the specified cell of an `n` by `n` square
+    /// is mapped by each symmetry operation, including itself.
+    fn orbit(self, n: u32, (i, j): (u32, u32)) -> Vec<(u32, u32)> {
+        let turn = |(i, j): (u32, u32)| (j, n - 1 - i);
+        let flip = |(i, j): (u32, u32)| (i, n - 1 - j);
+        let quarter_turns = |p| [p, turn(p), turn(turn(p)), turn(turn(turn(p)))];
+        match self {
+            Symmetry::None => vec![(i, j)],
+            Symmetry::C2 => vec![(i, j), turn(turn((i, j)))],
+            Symmetry::C4 => quarter_turns((i, j)).to_vec(),
+            Symmetry::D8 => [quarter_turns((i, j)), quarter_turns(flip((i, j)))].concat(),
+        }
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Symmetry::None => "C1",
+            Symmetry::C2 => "C2",
+            Symmetry::C4 => "C4",
+            Symmetry::D8 => "D8",
+        })
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Symmetry::ALL
+            .into_iter()
+            .find(|symmetry| symmetry.to_string().eq_ignore_ascii_case(s))
+            .ok_or(())
+    }
+}
+
+/// The largest soup, in cells per side.
+pub const MAX_SIZE: u32 = 1024;
+
+/// Instructions for generating a random square pattern.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Soup {
+    pub seed: u32,
+    /// The expected percentage of live cells.
+    pub density: u8,
+    /// The side length of the square, in cells.
+    pub size: u32,
+    pub symmetry: Symmetry,
+}
+
+impl Default for Soup {
+    fn default() -> Self {
+        Soup {
+            seed: 0,
+            density: 50,
+            size: 64,
+            symmetry: Symmetry::None,
+        }
+    }
+}
+
+impl Soup {
+    /// Returns the square filled by [`Self::generate`].
+    pub fn bounds(&self) -> Bounds {
+        let min = -(self.size / 2).cast_signed();
+        let max = min + self.size.cast_signed() - 1;
+        Bounds {
+            min: Point { i: min, j: min },
+            max: Point { i: max, j: max },
+        }
+    }
+
+    /// Returns a universe holding this soup, centered on the origin.
+    pub fn generate(&self) -> Universe {
+        let n = self.size;
+        let mut random = LinearCongruentialGenerator::from_seed(self.seed);
+        // Compare against the high bits of each random number, since the low
+        // bits of a power-of-two-modulus LCG are far from random.
+        let threshold = u32::from(self.density.min(100)) * (u32::MAX / 100);
+        let live: Vec<bool> = (0..n * n).map(|_| random.next_u32() < threshold).collect();
+        let mut universe = Universe::new();
+        let min = self.bounds().min;
+        for i in 0..n {
+            for j in 0..n {
+                // Every cell in an orbit copies the first cell in that orbit.
+                let (ri, rj) = self
+                    .symmetry
+                    .orbit(n, (i, j))
+                    .into_iter()
+                    .min()
+                    .unwrap_or((i, j));
+                if live[u32_to_usize(ri * n + rj)] {
+                    let p = Point {
+                        i: min.i + i.cast_signed(),
+                        j: min.j + j.cast_signed(),
+                    };
+                    universe.set(p, Cell::LIVE);
+                }
+            }
+        }
+        universe
+    }
+
+    /// Parses a URL query string, such as `?seed=42&density=30&symmetry=C4`.
+    /// Returns [`None`] if there is no seed. Other parameters are optional.
+    pub fn from_query(query: &str) -> Option<Soup> {
+        let mut soup = Soup::default();
+        let mut seed = None;
+        for pair in query.trim_start_matches('?').split('&') {
+            let Some((key, value)) = pair.split_once('=') else {
+                continue;
+            };
+            match key {
+                "seed" => seed = value.parse().ok(),
+                "density" => soup.density = value.parse().unwrap_or(soup.density).min(100),
+                "size" => soup.size = value.parse().unwrap_or(soup.size).clamp(1, MAX_SIZE),
+                "symmetry" => soup.symmetry = value.parse().unwrap_or_default(),
+                _ => (),
+            }
+        }
+        soup.seed = seed?;
+        Some(soup)
+    }
+
+    /// Returns a URL query string from which [`Self::from_query`] recreates
+    /// this soup.
+    pub fn to_query(self) -> String {
+        let Soup {
+            seed,
+            density,
+            size,
+            symmetry,
+        } = self;
+        format!("?seed={seed}&density={density}&size={size}&symmetry={symmetry}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_soup() {
+        let soup = Soup {
+            seed: 7,
+            ..Soup::default()
+        };
+        assert_eq!(
+            soup.generate().sorted_cells(),
+            soup.generate().sorted_cells()
+        );
+        let other = Soup { seed: 8, ..soup };
+        assert_ne!(
+            soup.generate().sorted_cells(),
+            other.generate().sorted_cells()
+        );
+    }
+
+    #[test]
+    fn symmetric_soups_are_symmetric() {
+        for symmetry in [Symmetry::C2, Symmetry::C4, Symmetry::D8] {
+            let soup = Soup {
+                seed: 3,
+                size: 16,
+                symmetry,
+                ..Soup::default()
+            };
+            let universe = soup.generate();
+            for p in universe.cells() {
+                // The square spans -8..8, so a half turn maps i to -1 - i.
+                let turned = Point {
+                    i: -1 - p.i,
+                    j: -1 - p.j,
+                };
+                assert_eq!(universe.at(turned), Cell::LIVE, "{symmetry}");
+                if symmetry == Symmetry::C2 {
+                    continue;
+                }
+                let quarter = Point {
+                    i: p.j,
+                    j: -1 - p.i,
+                };
+                assert_eq!(universe.at(quarter), Cell::LIVE, "{symmetry}");
+                if symmetry == Symmetry::D8 {
+                    let reflected = Point {
+                        i: p.i,
+                        j: -1 - p.j,
+                    };
+                    assert_eq!(universe.at(reflected), Cell::LIVE, "{symmetry}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn query_round_trip() {
+        let soup = Soup {
+            seed: 12345,
+            density: 35,
+            size: 20,
+            symmetry: Symmetry::D8,
+        };
+        assert_eq!(Soup::from_query(&soup.to_query()), Some(soup));
+        assert_eq!(Soup::from_query("?density=10"), None);
+        assert_eq!(Soup::from_query("?seed=9").map(|soup| soup.seed), Some(9));
+    }
+}
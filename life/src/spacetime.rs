@@ -0,0 +1,195 @@
+//! A space-time diagram of a one-dimensional automaton, in which each new
+//! generation is drawn as a row at the bottom of the canvas, while the rows
+//! above it scroll up.
+
+use std::{cell::RefCell, rc::Rc};
+
+use wasm_bindgen::prelude::*;
+use web_sys::{CanvasRenderingContext2d, Element, Event, Performance};
+
+use easel::{Easel, RenderContext, Result, canvas_size};
+use sugar::{Listener, prelude::*};
+use system::{System, u32_to_usize, usize_to_u32};
+
+use crate::app::fit_canvas;
+use crate::controls::{ElementaryControls, SpeedControls};
+use crate::elementary::{Elementary, Setup};
+use crate::speed::{Pace, Speed};
+
+/// Canvas pixels per cell.
+const CELL_PX: u32 = 3;
+
+/// Milliseconds per frame spent running generations, as in the plane.
+const TICK_BUDGET_MS: f64 = 12.0;
+
+const LIVE_COLOR: &str = "hsl(145, 19%, 45%)"; // Dark jade.
+
+/// State shared by the easel's render callback and the event handlers.
+struct World {
+    automaton: Elementary,
+    /// How the automaton was started, so that it can be started again at a new
+    /// width.
+    setup: Setup,
+    speed: Speed,
+    pace: Pace,
+}
+
+/// Scrolls the diagram up by one row, and draws the current generation in the
+/// row left at the bottom.
+fn draw_row(context: &CanvasRenderingContext2d, automaton: &Elementary) {
+    let Some(canvas) = context.canvas() else {
+        return;
+    };
+    let size = canvas_size(&canvas);
+    let (width, height) = (f64::from(size.width), f64::from(size.height));
+    let cell = f64::from(CELL_PX);
+    _ = context.draw_image_with_html_canvas_element(&canvas, 0.0, -cell);
+    let y = height - cell;
+    context.clear_rect(0.0, y, width, cell);
+    context.set_fill_style_str(LIVE_COLOR);
+    for (j, _) in automaton.cells().iter().enumerate().filter(|&(_, &c)| c) {
+        let x = f64::from(usize_to_u32(j) * CELL_PX);
+        context.fill_rect(x, y, cell, cell);
+    }
+}
+
+/// Starts the automaton again, as wide as the canvas, on a blank diagram.
+fn restart(context: &CanvasRenderingContext2d, world: &mut World, setup: Setup) {
+    let size = context.canvas().map(canvas_size).unwrap_or_default();
+    let width = u32_to_usize(size.width / CELL_PX);
+    world.automaton = Elementary::new(setup, width);
+    world.setup = setup;
+    world.pace = Pace::new();
+    context.clear_rect(0.0, 0.0, size.width.into(), size.height.into());
+    draw_row(context, &world.automaton);
+}
+
+/// Returns the easel's render callback, which runs whatever generations are
+/// due, drawing each.
+fn renderer(system: &System, world: &Rc<RefCell<World>>) -> impl FnMut(RenderContext) + 'static {
+    let world = Rc::clone(world);
+    let clock = system.window.performance();
+    move |easel: RenderContext| {
+        let mut world = world.borrow_mut();
+        let world = &mut *world;
+        let now = || clock.as_ref().map_or(f64::INFINITY, Performance::now);
+        let deadline = now() + TICK_BUDGET_MS;
+        world
+            .pace
+            .accrue(world.speed, easel.delta_ms.unwrap_or_default());
+        while world.pace.take() {
+            world.automaton.step();
+            draw_row(easel.canvas, &world.automaton);
+            if now() > deadline {
+                world.pace.forgive();
+                break;
+            }
+        }
+        let automaton = &world.automaton;
+        let caption = format!("Rule {} @ {}", automaton.rule(), automaton.generation());
+        easel.caption.set_text_content(Some(&caption));
+    }
+}
+
+/// Listens for changes to the speed and setup, for single steps, and for
+/// window resizes, which restart the automaton at the new width.
+fn listeners(
+    system: &System,
+    speed: &Rc<SpeedControls>,
+    controls: &Rc<ElementaryControls>,
+    world: &Rc<RefCell<World>>,
+    easel: &Rc<RefCell<Easel>>,
+) -> Result<Vec<Listener>> {
+    let change_speed = {
+        let (speed, world) = (Rc::clone(speed), Rc::clone(world));
+        move |_: Event| {
+            let mut world = world.borrow_mut();
+            world.speed = speed.speed();
+            world.pace = Pace::new();
+        }
+    };
+
+    let step = {
+        let (world, easel) = (Rc::clone(world), Rc::clone(easel));
+        move |_: Event| {
+            let mut world = world.borrow_mut();
+            world.automaton.step();
+            easel
+                .borrow()
+                .borrow_canvas_context(|context| draw_row(context, &world.automaton));
+        }
+    };
+
+    // Also puts the setup in the page URL, so that it can be shared.
+    let start = {
+        let (system, controls) = (system.clone(), Rc::clone(controls));
+        let (world, easel) = (Rc::clone(world), Rc::clone(easel));
+        move |_: Event| {
+            let setup = controls.setup();
+            let mut world = world.borrow_mut();
+            easel
+                .borrow()
+                .borrow_canvas_context(|context| restart(context, &mut world, setup));
+            if let Ok(history) = system.window.history() {
+                _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&setup.to_query()));
+            }
+        }
+    };
+
+    let resize = {
+        let (system, world, easel) = (system.clone(), Rc::clone(world), Rc::clone(easel));
+        move |_: Event| {
+            let size = fit_canvas(&system);
+            let mut easel = easel.borrow_mut();
+            if size == canvas_size(easel.canvas()) {
+                return;
+            }
+            easel.resize_canvas(size);
+            let mut world = world.borrow_mut();
+            let setup = world.setup;
+            easel.borrow_canvas_context(|context| restart(context, &mut world, setup));
+        }
+    };
+
+    Ok(vec![
+        Listener::new(speed.select(), "change", change_speed)?,
+        Listener::new(&speed.step, "click", step)?,
+        Listener::new(controls.rule_input(), "change", start.clone())?,
+        Listener::new(controls.start_select(), "change", start.clone())?,
+        Listener::new(&controls.restart, "click", start)?,
+        Listener::new(system.window.as_ref(), "resize", resize)?,
+    ])
+}
+
+/// Returns the root element of the diagram and its controls, the easel, and
+/// the listeners that must be kept alive.
+///
+/// # Errors
+///
+/// Will return [`Err`] if DOM interaction fails.
+pub fn new(system: &System, setup: Setup) -> Result<(Element, Rc<RefCell<Easel>>, Vec<Listener>)> {
+    let world = Rc::new(RefCell::new(World {
+        automaton: Elementary::new(setup, 0),
+        setup,
+        speed: Speed::DEFAULT,
+        pace: Pace::new(),
+    }));
+    let mut easel = Easel::start(system, renderer(system, &world))?;
+    easel.resize_canvas(fit_canvas(system));
+    easel.borrow_canvas_context(|context| restart(context, &mut world.borrow_mut(), setup));
+
+    let speed = Rc::new(SpeedControls::new(system)?);
+    let controls = Rc::new(ElementaryControls::new(system, setup)?);
+    let root = DIV
+        .class("life")
+        .child2(
+            easel.as_ref(),
+            DIV.class("life-controls")
+                .child2(&speed.root, &controls.root),
+        )
+        .to_element(system)?;
+
+    let easel = Rc::new(RefCell::new(easel));
+    let listeners = listeners(system, &speed, &controls, &world, &easel)?;
+    Ok((root, easel, listeners))
+}
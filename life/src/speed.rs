@@ -0,0 +1,123 @@
+//! Simulation speed, independent of the display frame rate.
+
+use std::fmt;
+
+/// How many generations to run per second of wall clock time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Speed {
+    PerSecond(f64),
+    /// As many generations as fit in each frame's time budget.
+    Unlimited,
+}
+
+impl Speed {
+    pub const ALL: [Speed; 11] = [
+        Speed::PerSecond(0.5),
+        Speed::PerSecond(1.0),
+        Speed::PerSecond(2.0),
+        Speed::PerSecond(5.0),
+        Speed::PerSecond(10.0),
+        Speed::PerSecond(30.0),
+        Speed::PerSecond(60.0),
+        Speed::PerSecond(120.0),
+        Speed::PerSecond(240.0),
+        Speed::PerSecond(1000.0),
+        Speed::Unlimited,
+    ];
+
+    /// The speed selected when the page loads, which matches a typical display
+    /// refresh rate.
+    pub const DEFAULT: Speed = Speed::PerSecond(60.0);
+}
+
+impl fmt::Display for Speed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Speed::PerSecond(rate) => write!(f, "{rate} gen/s"),
+            Speed::Unlimited => write!(f, "Max"),
+        }
+    }
+}
+
+/// Tracks the generations due, as time passes.
+#[derive(Default)]
+pub struct Pace {
+    /// Generations due but not yet run, including a fraction of the next.
+    owed: f64,
+}
+
+impl Pace {
+    pub fn new() -> Pace {
+        Pace::default()
+    }
+
+    /// Accrues the generations due over the specified elapsed time.
+    pub fn accrue(&mut self, speed: Speed, delta_ms: f64) {
+        self.owed = match speed {
+            Speed::PerSecond(rate) => self.owed + rate * delta_ms / 1000.0,
+            Speed::Unlimited => f64::INFINITY,
+        };
+    }
+
+    /// Returns true, and counts a generation as run, if a generation is due.
+    pub fn take(&mut self) -> bool {
+        let due = self.owed >= 1.0;
+        if due {
+            self.owed -= 1.0;
+        }
+        due
+    }
+
+    /// Forgets any generations still due. Call this when running out of time,
+    /// so that the simulation slows down rather than falling ever further
+    /// behind.
+    pub fn forgive(&mut self) {
+        self.owed = if self.owed.is_finite() {
+            self.owed.fract()
+        } else {
+            0.0
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(pace: &mut Pace, speed: Speed, delta_ms: f64) -> usize {
+        pace.accrue(speed, delta_ms);
+        std::iter::from_fn(|| pace.take().then_some(())).count()
+    }
+
+    #[test]
+    fn slow_speeds_skip_frames() {
+        let mut pace = Pace::new();
+        let frames: Vec<usize> = (0..8)
+            .map(|_| run(&mut pace, Speed::PerSecond(0.5), 500.0))
+            .collect();
+        assert_eq!(frames, [0, 0, 0, 1, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn fast_speeds_run_several_ticks_per_frame() {
+        let mut pace = Pace::new();
+        let total: usize = (0..60)
+            .map(|_| run(&mut pace, Speed::PerSecond(240.0), 1000.0 / 60.0))
+            .sum();
+        // Allow for rounding error in the last frame.
+        assert!((239..=240).contains(&total), "{total}");
+    }
+
+    #[test]
+    fn forgiveness_drops_the_backlog() {
+        let mut pace = Pace::new();
+        pace.accrue(Speed::PerSecond(1000.0), 1000.5);
+        assert!(pace.take());
+        pace.forgive();
+        assert!(!pace.take());
+        pace.accrue(Speed::Unlimited, 16.0);
+        assert!(pace.take());
+        pace.forgive();
+        assert!(!pace.take());
+    }
+}
@@ -0,0 +1,196 @@
+//! Statistics about a run, including detection of periodic behavior.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::universe::{Point, Turnover, Universe};
+
+/// The number of recent populations kept for plotting.
+pub const SPARKLINE_LEN: usize = 240;
+
+/// The longest period detected. Longer cycles go unnoticed.
+const MAX_PERIOD: usize = 64;
+
+/// What the universe has settled into, if anything.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Settled {
+    Empty,
+    StillLife,
+    Oscillator {
+        period: usize,
+    },
+    /// The same shape, shifted by `offset` every `period` generations.
+    Spaceship {
+        period: usize,
+        offset: Point,
+    },
+}
+
+impl fmt::Display for Settled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Settled::Empty => write!(f, "empty"),
+            Settled::StillLife => write!(f, "still life"),
+            Settled::Oscillator { period } => write!(f, "oscillator of period {period}"),
+            Settled::Spaceship { period, offset } => write!(
+                f,
+                "spaceship of period {period}, moving ({}, {})",
+                offset.j, offset.i
+            ),
+        }
+    }
+}
+
+/// Summarizes a generation, so that later generations can be compared to it
+/// cheaply.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Fingerprint {
+    /// A hash of the live cells, relative to the top left corner of their
+    /// bounds, so that translated copies have equal hashes.
+    shape: u64,
+    /// The top left corner of the bounds of the live cells.
+    origin: Point,
+    population: u64,
+}
+
+/// Mixes the bits of a 64-bit value, per [SplitMix64].
+///
+/// [SplitMix64]: https://prng.di.unimi.it/splitmix64.c
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+impl Fingerprint {
+    fn new(universe: &Universe) -> Fingerprint {
+        let origin = universe
+            .bounds()
+            .map(|bounds| bounds.min)
+            .unwrap_or_default();
+        // Summing per-cell hashes makes the result independent of the order in
+        // which cells are visited, so there's no need to sort them.
+        let shape = universe
+            .states()
+            .map(|(p, c)| {
+                let i = u64::from((p.i - origin.i).cast_unsigned());
+                let j = u64::from((p.j - origin.j).cast_unsigned());
+                mix(mix(i << 32 | j) ^ u64::from(c.0))
+            })
+            .fold(0, u64::wrapping_add);
+        Fingerprint {
+            shape,
+            origin,
+            population: universe.population(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Stats {
+    /// Recent populations, oldest first.
+    populations: VecDeque<u64>,
+    /// Changes during the most recent tick.
+    turnover: Turnover,
+    /// Recent fingerprints, oldest first.
+    history: VecDeque<Fingerprint>,
+}
+
+impl Stats {
+    pub fn new() -> Stats {
+        Stats::default()
+    }
+
+    pub fn populations(&self) -> impl ExactSizeIterator<Item = u64> {
+        self.populations.iter().copied()
+    }
+
+    pub fn turnover(&self) -> Turnover {
+        self.turnover
+    }
+
+    /// Records the state of the universe after a tick.
+    pub fn record(&mut self, universe: &Universe, turnover: Turnover) {
+        if self.populations.len() == SPARKLINE_LEN {
+            self.populations.pop_front();
+        }
+        self.populations.push_back(universe.population());
+        self.turnover = turnover;
+        if self.history.len() > MAX_PERIOD {
+            self.history.pop_front();
+        }
+        self.history.push_back(Fingerprint::new(universe));
+    }
+
+    /// Forgets recorded generations for the purpose of period detection. Call
+    /// this after editing the universe, since earlier generations no longer
+    /// predict later ones.
+    pub fn interrupt(&mut self) {
+        self.history.clear();
+        self.turnover = Turnover::default();
+    }
+
+    /// Returns the shortest cycle among recent generations, if any.
+    pub fn settled(&self) -> Option<Settled> {
+        let last = self.history.back()?;
+        if last.population == 0 {
+            return Some(Settled::Empty);
+        }
+        let (period, then) = (1..)
+            .zip(self.history.iter().rev().skip(1))
+            .find(|(_, f)| f.shape == last.shape && f.population == last.population)
+            .map(|(period, f)| (period, f.origin))?;
+        let offset = Point {
+            i: last.origin.i - then.i,
+            j: last.origin.j - then.j,
+        };
+        Some(match (period, offset) {
+            (_, offset) if offset != Point::default() => Settled::Spaceship { period, offset },
+            (1, _) => Settled::StillLife,
+            (period, _) => Settled::Oscillator { period },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::universe::Cell;
+
+    fn run(cells: &[(i32, i32)], ticks: usize) -> Option<Settled> {
+        let mut universe = Universe::new();
+        for &(i, j) in cells {
+            universe.set(Point { i, j }, Cell::LIVE);
+        }
+        let mut stats = Stats::new();
+        stats.record(&universe, Turnover::default());
+        for _ in 0..ticks {
+            let turnover = universe.tick();
+            stats.record(&universe, turnover);
+        }
+        stats.settled()
+    }
+
+    #[test]
+    fn detects_still_lifes_and_oscillators() {
+        let block = [(0, 0), (0, 1), (1, 0), (1, 1)];
+        assert_eq!(run(&block, 1), Some(Settled::StillLife));
+        let blinker = [(0, 0), (0, 1), (0, 2)];
+        assert_eq!(run(&blinker, 1), None);
+        assert_eq!(run(&blinker, 2), Some(Settled::Oscillator { period: 2 }));
+        assert_eq!(run(&[(0, 0)], 1), Some(Settled::Empty));
+    }
+
+    #[test]
+    fn detects_spaceships() {
+        let glider = [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+        assert_eq!(run(&glider, 3), None);
+        assert_eq!(
+            run(&glider, 4),
+            Some(Settled::Spaceship {
+                period: 4,
+                offset: Point { i: 1, j: 1 }
+            })
+        );
+    }
+}
@@ -1,133 +1,974 @@
-use std::mem;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
 
-use system::{SizeU32, u32_to_usize, usize_to_u32};
+use system::{SizeU32, u32_to_usize};
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub enum Cell {
-    Dead,
-    Live,
-}
+use crate::grid::Grid;
+use crate::ltl::{self, Counter, Sums};
+use crate::pattern::Pattern;
+use crate::rule::Rule;
+
+/// The state of a cell. Rules with more than two states give meaning to
+/// states beyond dead and live.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Cell(pub u8);
 
 impl Cell {
-    fn to_u8(self) -> u8 {
+    pub const DEAD: Cell = Cell(0);
+    pub const LIVE: Cell = Cell(1);
+}
+
+/// Row and column indexes, which may be negative, because the universe is
+/// unbounded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Point {
+    pub i: i32,
+    pub j: i32,
+}
+
+/// The smallest rectangle containing a set of points, inclusive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    pub fn contains(&self, p: Point) -> bool {
+        (self.min.i..=self.max.i).contains(&p.i) && (self.min.j..=self.max.j).contains(&p.j)
+    }
+
+    /// Returns the middle cell, rounding up and to the left.
+    pub fn center(&self) -> Point {
+        Point {
+            i: self.min.i + (self.max.i - self.min.i) / 2,
+            j: self.min.j + (self.max.j - self.min.j) / 2,
+        }
+    }
+
+    /// Returns the smallest rectangle containing every point, or [`None`] if
+    /// there are none.
+    pub fn enclosing(points: impl IntoIterator<Item = Point>) -> Option<Bounds> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bounds = Bounds {
+            min: first,
+            max: first,
+        };
+        points.for_each(|p| bounds.include(p));
+        Some(bounds)
+    }
+
+    pub fn include(&mut self, p: Point) {
+        self.min.i = self.min.i.min(p.i);
+        self.min.j = self.min.j.min(p.j);
+        self.max.i = self.max.i.max(p.i);
+        self.max.j = self.max.j.max(p.j);
+    }
+
+    pub fn size(&self) -> SizeU32 {
+        SizeU32 {
+            height: self.max.i.abs_diff(self.min.i) + 1,
+            width: self.max.j.abs_diff(self.min.j) + 1,
+        }
+    }
+}
+
+/// How the edges of the universe behave.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Topology {
+    /// Unbounded in every direction.
+    #[default]
+    Plane,
+    /// A rectangle with its top left corner at the origin, whose opposite
+    /// edges are joined, so that cells leaving one edge reenter at the other.
+    Torus(SizeU32),
+}
+
+impl Topology {
+    /// Returns the cells of a torus, or [`None`] for the plane.
+    pub fn bounds(self) -> Option<Bounds> {
+        match self {
+            Topology::Plane => None,
+            Topology::Torus(size) => Some(Bounds {
+                min: Point::default(),
+                max: Point {
+                    i: size.height.cast_signed() - 1,
+                    j: size.width.cast_signed() - 1,
+                },
+            }),
+        }
+    }
+
+    /// Maps any point to the equivalent point within the bounds, if any.
+    fn wrap(self, p: Point) -> Point {
         match self {
-            Cell::Dead => 0,
-            Cell::Live => 1,
+            Topology::Plane => p,
+            Topology::Torus(size) => Point {
+                i: p.i.rem_euclid(size.height.cast_signed()),
+                j: p.j.rem_euclid(size.width.cast_signed()),
+            },
         }
     }
+
+    /// Returns the number of tiles per column and row needed to cover a torus.
+    fn tiles(self) -> Option<Point> {
+        let bounds = self.bounds()?;
+        Some(Point {
+            i: bounds.max.i.div_euclid(TILE) + 1,
+            j: bounds.max.j.div_euclid(TILE) + 1,
+        })
+    }
 }
 
-/// Zero-based row and column indexes.
-#[derive(Clone, Copy)]
-pub struct Point {
-    pub i: u32,
-    pub j: u32,
+/// Golly's notation for the shape of a universe, which follows the rule after
+/// a colon: empty for the plane, or `T` and the width and height of a torus.
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Topology::Plane => Ok(()),
+            Topology::Torus(SizeU32 { width, height }) => write!(f, "T{width},{height}"),
+        }
+    }
+}
+
+impl FromStr for Topology {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Topology::Plane);
+        }
+        let (width, height) = s
+            .strip_prefix(['T', 't'])
+            .and_then(|size| size.split_once(','))
+            .ok_or(())?;
+        let size = SizeU32 {
+            width: width.trim().parse().map_err(|_| ())?,
+            height: height.trim().parse().map_err(|_| ())?,
+        };
+        if size.width == 0 || size.height == 0 {
+            return Err(());
+        }
+        Ok(Topology::Torus(size))
+    }
+}
+
+/// Side length of a tile, in cells.
+const TILE: i32 = 32;
+/// [`TILE`], for indexing.
+const SIDE: usize = u32_to_usize(TILE.unsigned_abs());
+const TILE_AREA: usize = SIDE * SIDE;
+
+/// Returns the key of the tile containing the specified point, and the index
+/// of the point within that tile.
+fn locate(p: Point) -> (Point, usize) {
+    let key = Point {
+        i: p.i.div_euclid(TILE),
+        j: p.j.div_euclid(TILE),
+    };
+    let index = p.i.rem_euclid(TILE) * TILE + p.j.rem_euclid(TILE);
+    (key, u32_to_usize(index.cast_unsigned()))
+}
+
+/// A square region of the universe, holding at least one cell that isn't dead.
+#[derive(Clone)]
+struct Tile {
+    cells: [Cell; TILE_AREA],
+    /// The number of generations each live cell has survived.
+    ages: [u16; TILE_AREA],
+    /// The number of cells that aren't dead.
+    population: u32,
+}
+
+impl Tile {
+    fn new() -> Tile {
+        Tile {
+            cells: [Cell::DEAD; TILE_AREA],
+            ages: [0; TILE_AREA],
+            population: 0,
+        }
+    }
+
+    fn set(&mut self, index: usize, c: Cell) {
+        let old = std::mem::replace(&mut self.cells[index], c);
+        self.ages[index] = 0;
+        self.population =
+            self.population + u32::from(c != Cell::DEAD) - u32::from(old != Cell::DEAD);
+    }
+}
+
+/// Returns the position of the cell at the specified index of a tile.
+fn point_at(key: Point, index: usize) -> Point {
+    let index = i32::try_from(index).unwrap_or_default();
+    Point {
+        i: key.i * TILE + index / TILE,
+        j: key.j * TILE + index % TILE,
+    }
 }
 
-fn at(rows: &[Vec<Cell>], p: Point) -> Cell {
-    rows[u32_to_usize(p.i)][u32_to_usize(p.j)]
+/// Width of the margin around a tile in a window, which is enough to reach the
+/// farthest neighbor on any grid.
+const GRID_MARGIN: usize = u32_to_usize(Grid::REACH.unsigned_abs());
+
+/// Width of the margin for Larger than Life rules, which reach farther.
+const LTL_MARGIN: usize = u32_to_usize(ltl::MAX_RANGE.unsigned_abs());
+
+/// A tile and the margin of cells around it, one byte per cell: 1 if live,
+/// otherwise 0, so that every cell in the tile has all of its neighbors at
+/// hand. The margin is never wider than a tile. The area is that of the whole
+/// window, which the compiler can't yet work out from the margin itself.
+struct Window<const MARGIN: usize, const AREA: usize> {
+    cells: [u8; AREA],
+    /// Counts neighbors for Larger than Life rules, which are too many to count
+    /// one by one.
+    counter: Option<Counter>,
 }
 
-fn count_live_neighbors(rows: &[Vec<Cell>], size: SizeU32, p: Point) -> u8 {
-    let mut count = 0;
-    let SizeU32 {
-        height: h,
-        width: w,
-    } = size;
-    for di in [h - 1, 0, 1] {
-        for dj in [w - 1, 0, 1] {
-            if di == 0 && dj == 0 {
-                continue;
+/// Windows for rules on grids, and for Larger than Life rules.
+type GridWindow = Window<GRID_MARGIN, { (SIDE + 2 * GRID_MARGIN) * (SIDE + 2 * GRID_MARGIN) }>;
+type LtlWindow = Window<LTL_MARGIN, { (SIDE + 2 * LTL_MARGIN) * (SIDE + 2 * LTL_MARGIN) }>;
+
+impl<const MARGIN: usize, const AREA: usize> Window<MARGIN, AREA> {
+    /// The tile plus a margin on each side.
+    const WIDTH: usize = SIDE + 2 * MARGIN;
+
+    fn new(counter: Option<Counter>) -> Self {
+        debug_assert_eq!(AREA, Self::WIDTH * Self::WIDTH);
+        Window {
+            cells: [0; AREA],
+            counter,
+        }
+    }
+
+    /// Returns the margin, in rows or columns.
+    fn reach() -> i32 {
+        i32::try_from(MARGIN).unwrap_or_default()
+    }
+
+    /// Copies the tile at `key` and the margin around it from `tiles`.
+    fn fill(&mut self, tiles: &HashMap<Point, Tile>, key: Point) {
+        let neighbor = |di: i32, dj: i32| {
+            tiles.get(&Point {
+                i: key.i + di,
+                j: key.j + dj,
+            })
+        };
+        let block: [[Option<&Tile>; 3]; 3] = [
+            [neighbor(-1, -1), neighbor(-1, 0), neighbor(-1, 1)],
+            [neighbor(0, -1), neighbor(0, 0), neighbor(0, 1)],
+            [neighbor(1, -1), neighbor(1, 0), neighbor(1, 1)],
+        ];
+        for (wi, row) in self.cells.chunks_exact_mut(Self::WIDTH).enumerate() {
+            // Offset by one tile, less the margin, so that the block index is
+            // never negative.
+            let wi = wi + SIDE - MARGIN;
+            let (bi, ti) = (wi / SIDE, wi % SIDE);
+            for (wj, byte) in row.iter_mut().enumerate() {
+                let wj = wj + SIDE - MARGIN;
+                let (bj, tj) = (wj / SIDE, wj % SIDE);
+                *byte = block[bi][bj]
+                    .map(|tile| u8::from(tile.cells[ti * SIDE + tj] == Cell::LIVE))
+                    .unwrap_or_default();
             }
-            let q = Point {
-                i: (p.i + di) % h,
-                j: (p.j + dj) % w,
-            };
-            count += at(rows, q).to_u8();
         }
     }
-    count
+
+    /// Like [`Self::fill`], but looks up each cell separately so that the
+    /// margin can wrap around the edges of a torus. Slower, so only used for
+    /// tiles at the edges.
+    fn fill_wrapped(&mut self, universe: &Universe, key: Point) {
+        let reach = Self::reach();
+        for (i, row) in (-reach..TILE + reach).zip(self.cells.chunks_exact_mut(Self::WIDTH)) {
+            for (j, byte) in (-reach..TILE + reach).zip(row.iter_mut()) {
+                let p = Point {
+                    i: key.i * TILE + i,
+                    j: key.j * TILE + j,
+                };
+                *byte = u8::from(universe.at(p) == Cell::LIVE);
+            }
+        }
+    }
+
+    /// Returns the number of live neighbors of the cell at row `i` and column
+    /// `j` of the window, on a grid.
+    fn count(&self, grid: Grid, i: usize, j: usize) -> u8 {
+        let (window, width) = (&self.cells, Self::WIDTH);
+        let at = |i: usize, j: usize| window[i * width + j];
+        let block = || {
+            window[(i - 1) * width + j - 1..=(i - 1) * width + j + 1]
+                .iter()
+                .chain(&window[i * width + j - 1..=i * width + j + 1])
+                .chain(&window[(i + 1) * width + j - 1..=(i + 1) * width + j + 1])
+                .sum::<u8>()
+                - at(i, j)
+        };
+        match grid {
+            Grid::Square => block(),
+            Grid::Hexagonal => block() - at(i - 1, j + 1) - at(i + 1, j - 1),
+            Grid::Triangular => {
+                // Tiles span an even number of columns and rows, and so does
+                // the margin on this grid, so cells point the same way in the
+                // window as in the universe.
+                let p = Point {
+                    i: i32::try_from(i).unwrap_or_default(),
+                    j: i32::try_from(j).unwrap_or_default(),
+                };
+                grid.neighbors(p)
+                    .iter()
+                    .map(|&(di, dj)| {
+                        let i = i.strict_add_signed(isize::try_from(di).unwrap_or_default());
+                        let j = j.strict_add_signed(isize::try_from(dj).unwrap_or_default());
+                        at(i, j)
+                    })
+                    .sum()
+            }
+        }
+    }
+
+    /// Computes the next state of the tile at the center of the window, whose
+    /// current state is `old`.
+    fn step(&self, old: Option<&Tile>, rule: Rule) -> Tile {
+        if let Some(counter) = &self.counter {
+            let sums = Sums::new(&self.cells, Self::WIDTH);
+            Self::step_with(old, rule, |i, j| {
+                counter.count(&sums, &self.cells, Self::WIDTH, i, j)
+            })
+        } else {
+            let grid = rule.grid();
+            Self::step_with(old, rule, |i, j| u16::from(self.count(grid, i, j)))
+        }
+    }
+
+    /// Like [`Self::step`], counting the neighbors of the cell at each row and
+    /// column of the window with the specified function.
+    fn step_with(old: Option<&Tile>, rule: Rule, count: impl Fn(usize, usize) -> u16) -> Tile {
+        let mut tile = Tile::new();
+        let mut index = 0;
+        for i in MARGIN..MARGIN + SIDE {
+            for j in MARGIN..MARGIN + SIDE {
+                let c = old.map_or(Cell::DEAD, |old| old.cells[index]);
+                let next = rule.next(c, count(i, j));
+                if next != Cell::DEAD {
+                    tile.set(index, next);
+                    if c == Cell::LIVE && next == Cell::LIVE {
+                        let age = old.map_or(0, |old| old.ages[index]);
+                        tile.ages[index] = age.saturating_add(1);
+                    }
+                }
+                index += 1;
+            }
+        }
+        tile
+    }
 }
 
-#[derive(Clone)]
+/// Reports each cell that differs between the old and new states of the tile at
+/// `key`. Returns the number of births, meaning dead cells that aren't anymore.
+fn diff(key: Point, old: Option<&Tile>, new: &Tile, changed: &mut impl FnMut(Point, Cell)) -> u32 {
+    let dead = [Cell::DEAD; TILE_AREA];
+    let old = old.map_or(&dead, |tile| &tile.cells);
+    let mut births = 0;
+    for (index, (&was, &is)) in old.iter().zip(&new.cells).enumerate() {
+        if was != is {
+            births += u32::from(was == Cell::DEAD);
+            changed(point_at(key, index), is);
+        }
+    }
+    births
+}
+
+/// The number of cells that came to life, or died, during one tick. Changes
+/// among other states aren't counted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Turnover {
+    pub births: u64,
+    pub deaths: u64,
+}
+
+/// A grid of cells, stored as a sparse map of square tiles. Only tiles holding
+/// cells that aren't dead are kept.
+#[derive(Clone, Default)]
 pub struct Universe {
-    /// Rectangular grid.
-    rows: Vec<Vec<Cell>>,
-    // Cache to avoid reallocations.
-    last: Vec<Vec<Cell>>,
+    tiles: HashMap<Point, Tile>,
+    topology: Topology,
+    rule: Rule,
+    /// The number of edits so far, not counting generations.
+    revision: u64,
 }
 
 impl Universe {
     pub fn new() -> Universe {
-        Universe {
-            rows: Vec::new(),
-            last: Vec::new(),
+        Universe::default()
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+        self.revision += 1;
+    }
+
+    /// Returns a number that changes whenever the universe is edited, but not
+    /// when it runs, so that copies with the same revision and generation
+    /// are known to be identical.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Estimates the number of bytes of memory used by the live cells.
+    pub fn footprint(&self) -> usize {
+        self.tiles.len() * size_of::<(Point, Tile)>()
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Changes the shape of the universe. When one torus is replaced by
+    /// another, the pattern stays centered. Live cells outside a new torus are
+    /// lost.
+    pub fn set_topology(&mut self, topology: Topology) {
+        let topology = match topology {
+            Topology::Plane => topology,
+            Topology::Torus(size) => Topology::Torus(SizeU32 {
+                width: size.width.max(1),
+                height: size.height.max(1),
+            }),
+        };
+        let offset = match (self.topology, topology) {
+            (Topology::Torus(old), Topology::Torus(new)) => Point {
+                i: (new.height.cast_signed() - old.height.cast_signed()).div_euclid(2),
+                j: (new.width.cast_signed() - old.width.cast_signed()).div_euclid(2),
+            },
+            _ => Point::default(),
+        };
+        let cells: Vec<(Point, Cell)> = self.states().collect();
+        self.tiles.clear();
+        self.topology = topology;
+        self.revision += 1;
+        let bounds = topology.bounds();
+        for (p, c) in cells {
+            let p = Point {
+                i: p.i + offset.i,
+                j: p.j + offset.j,
+            };
+            if bounds.is_none_or(|bounds| bounds.contains(p)) {
+                self.set(p, c);
+            }
         }
     }
 
-    pub fn resize(&mut self, size: SizeU32) {
-        self.rows.resize_with(u32_to_usize(size.height), Vec::new);
-        for row in &mut self.rows {
-            row.resize(u32_to_usize(size.width), Cell::Dead);
+    /// Moves every cell by the specified offset, wrapping on a torus.
+    pub fn translate(&mut self, offset: Point) {
+        let cells: Vec<(Point, Cell)> = self.states().collect();
+        self.tiles.clear();
+        for (p, c) in cells {
+            let p = Point {
+                i: p.i + offset.i,
+                j: p.j + offset.j,
+            };
+            self.set(p, c);
         }
     }
 
-    pub fn height(&self) -> u32 {
-        usize_to_u32(self.rows.len())
+    pub fn population(&self) -> u64 {
+        self.tiles
+            .values()
+            .map(|tile| u64::from(tile.population))
+            .sum()
+    }
+
+    /// Returns the smallest rectangle containing every live cell, or [`None`]
+    /// if there are no live cells.
+    pub fn bounds(&self) -> Option<Bounds> {
+        Bounds::enclosing(self.cells())
     }
 
-    pub fn width(&self) -> u32 {
-        self.rows
-            .first()
-            .map(Vec::len)
-            .map(usize_to_u32)
+    pub fn at(&self, p: Point) -> Cell {
+        let (key, index) = locate(self.topology.wrap(p));
+        self.tiles
+            .get(&key)
+            .map(|tile| tile.cells[index])
             .unwrap_or_default()
     }
 
-    fn size(&self) -> SizeU32 {
-        let height = self.height();
-        let width = self.width();
-        SizeU32 { height, width }
+    pub fn set(&mut self, p: Point, c: Cell) {
+        let (key, index) = locate(self.topology.wrap(p));
+        let tile = self.tiles.entry(key).or_insert_with(Tile::new);
+        tile.set(index, c);
+        self.revision += 1;
+        if tile.population == 0 {
+            self.tiles.remove(&key);
+        }
     }
 
-    pub fn at(&self, p: Point) -> Cell {
-        self.rows[u32_to_usize(p.i)][u32_to_usize(p.j)]
+    /// Returns the positions of all cells that aren't dead, in no particular
+    /// order.
+    pub fn cells(&self) -> impl Iterator<Item = Point> {
+        self.states().map(|(p, _)| p)
     }
 
-    pub fn set(&mut self, i: u32, j: u32, c: Cell) {
-        self.rows[u32_to_usize(i)][u32_to_usize(j)] = c;
+    /// Like [`Self::cells`], but sorted by row then column, so that universes
+    /// can be compared in tests.
+    #[cfg(test)]
+    pub fn sorted_cells(&self) -> Vec<Point> {
+        let mut cells: Vec<Point> = self.cells().collect();
+        cells.sort_unstable_by_key(|p| (p.i, p.j));
+        cells
     }
 
-    pub fn tick(&mut self) {
-        let size = self.size();
-        mem::swap(&mut self.rows, &mut self.last);
-        self.resize(size);
-        for i in 0..size.height {
-            for j in 0..size.width {
-                let p = Point { i, j };
-                let c = at(&self.last, p);
-                let n = count_live_neighbors(&self.last, size, p);
-                let d = match (c, n) {
-                    (Cell::Live, x) if !(2..=3).contains(&x) => Cell::Dead,
-                    (Cell::Live, _) | (Cell::Dead, 3) => Cell::Live,
-                    _ => c,
+    /// Like [`Self::cells`], but also returns the state of each cell.
+    pub fn states(&self) -> impl Iterator<Item = (Point, Cell)> {
+        self.tiles
+            .iter()
+            .flat_map(|(&key, tile)| tile_states(key, tile))
+    }
+
+    /// Returns the positions and states of cells within the specified bounds
+    /// that aren't dead, in no particular order.
+    pub fn states_within(&self, bounds: Bounds) -> impl Iterator<Item = (Point, Cell)> {
+        self.indexes_within(bounds)
+            .map(|(p, tile, index)| (p, tile.cells[index]))
+    }
+
+    /// Like [`Self::states_within`], but returns the number of generations each
+    /// cell has survived instead of its state.
+    pub fn ages_within(&self, bounds: Bounds) -> impl Iterator<Item = (Point, u16)> {
+        self.indexes_within(bounds)
+            .map(|(p, tile, index)| (p, tile.ages[index]))
+    }
+
+    /// Returns the position, tile, and index within the tile of each cell within
+    /// the specified bounds that isn't dead. Skips tiles entirely outside the
+    /// bounds.
+    fn indexes_within(&self, bounds: Bounds) -> impl Iterator<Item = (Point, &Tile, usize)> {
+        let (min, _) = locate(bounds.min);
+        let (max, _) = locate(bounds.max);
+        self.tiles
+            .iter()
+            .filter(move |(key, _)| {
+                (min.i..=max.i).contains(&key.i) && (min.j..=max.j).contains(&key.j)
+            })
+            .flat_map(|(&key, tile)| {
+                (0..TILE_AREA)
+                    .filter(|&index| tile.cells[index] != Cell::DEAD)
+                    .map(move |index| (point_at(key, index), tile, index))
+            })
+            .filter(move |&(p, _, _)| bounds.contains(p))
+    }
+
+    /// Sets the cells of the pattern, with its top left corner at the specified
+    /// point. Dead cells in the pattern leave the universe unchanged.
+    pub fn stamp(&mut self, pattern: &Pattern, at: Point) {
+        for (p, c) in pattern.states() {
+            let p = Point {
+                i: at.i + p.i,
+                j: at.j + p.j,
+            };
+            self.set(p, c);
+        }
+    }
+
+    pub fn tick(&mut self) -> Turnover {
+        self.tick_with(|_, _| ())
+    }
+
+    /// Like [`Self::tick`], but also reports the new state of each cell that
+    /// changed.
+    pub fn tick_with(&mut self, changed: impl FnMut(Point, Cell)) -> Turnover {
+        match self.rule {
+            Rule::Larger(ltl) => self.tick_in(LtlWindow::new(Some(Counter::new(ltl))), changed),
+            Rule::Totalistic { .. } | Rule::Wireworld => {
+                self.tick_in(GridWindow::new(None), changed)
+            }
+        }
+    }
+
+    /// Like [`Self::tick_with`], stepping each tile in the specified window.
+    fn tick_in<const MARGIN: usize, const AREA: usize>(
+        &mut self,
+        mut window: Window<MARGIN, AREA>,
+        mut changed: impl FnMut(Point, Cell),
+    ) -> Turnover {
+        let mut turnover = Turnover::default();
+        let tiles_per = self.topology.tiles();
+        let mut keys = HashSet::new();
+        for key in self.tiles.keys() {
+            for di in -1..=1 {
+                for dj in -1..=1 {
+                    let key = Point {
+                        i: key.i + di,
+                        j: key.j + dj,
+                    };
+                    keys.insert(tiles_per.map_or(key, |n| Point {
+                        i: key.i.rem_euclid(n.i),
+                        j: key.j.rem_euclid(n.j),
+                    }));
+                }
+            }
+        }
+        let bounds = self.topology.bounds();
+        let reach = Window::<MARGIN, AREA>::reach();
+        let mut tiles = HashMap::with_capacity(self.tiles.len());
+        for key in keys {
+            let old = self.tiles.get(&key);
+            // A window reaching past the edges of a torus needs wrapped cells
+            // in its margin, and cells beyond the edges must stay dead.
+            let edge = bounds.filter(|bounds| {
+                let min = Point {
+                    i: key.i * TILE - reach,
+                    j: key.j * TILE - reach,
+                };
+                let max = Point {
+                    i: key.i * TILE + TILE - 1 + reach,
+                    j: key.j * TILE + TILE - 1 + reach,
                 };
-                self.set(i, j, d);
+                !bounds.contains(min) || !bounds.contains(max)
+            });
+            let tile = if let Some(bounds) = edge {
+                window.fill_wrapped(self, key);
+                let mut tile = window.step(old, self.rule);
+                for (p, _) in tile_states(key, &tile).collect::<Vec<_>>() {
+                    if !bounds.contains(p) {
+                        tile.set(locate(p).1, Cell::DEAD);
+                    }
+                }
+                tile
+            } else {
+                window.fill(&self.tiles, key);
+                window.step(old, self.rule)
+            };
+            let births = diff(key, old, &tile, &mut changed);
+            let old = old.map_or(0, |tile| tile.population);
+            turnover.births += u64::from(births);
+            turnover.deaths += u64::from(old + births - tile.population);
+            if tile.population != 0 {
+                tiles.insert(key, tile);
             }
         }
+        self.tiles = tiles;
+        turnover
     }
 
-    pub fn speckle(&mut self) {
-        for i in 0..self.height() {
-            for j in 0..self.width() {
-                let k = i * self.width() + j;
-                let c = if k % 2 == 0 || k % 7 == 0 {
-                    Cell::Live
+    /// Fills a region of the specified size, with its top left corner at the
+    /// origin, with a fixed pattern of live cells.
+    pub fn speckle(&mut self, size: SizeU32) {
+        for i in 0..size.height {
+            for j in 0..size.width {
+                let k = i * size.width + j;
+                let c = if k.is_multiple_of(2) || k.is_multiple_of(7) {
+                    Cell::LIVE
                 } else {
-                    Cell::Dead
+                    Cell::DEAD
                 };
-                self.set(i, j, c);
+                let p = Point {
+                    i: i.cast_signed(),
+                    j: j.cast_signed(),
+                };
+                self.set(p, c);
+            }
+        }
+    }
+}
+
+fn tile_states(key: Point, tile: &Tile) -> impl Iterator<Item = (Point, Cell)> {
+    (0..TILE_AREA)
+        .zip(&tile.cells)
+        .filter(|&(_, &c)| c != Cell::DEAD)
+        .map(move |(index, &c)| (point_at(key, index), c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::soup::Soup;
+
+    fn universe(cells: &[(i32, i32)]) -> Universe {
+        let mut universe = Universe::new();
+        for &(i, j) in cells {
+            universe.set(Point { i, j }, Cell::LIVE);
+        }
+        universe
+    }
+
+    #[test]
+    fn blinker_oscillates_across_tile_edges() {
+        // Centered on a tile corner, so that every step crosses tile edges.
+        let mut universe = universe(&[(-1, 0), (0, 0), (1, 0)]);
+        let turnover = universe.tick();
+        assert_eq!(
+            universe.sorted_cells(),
+            [(0, -1), (0, 0), (0, 1)].map(|(i, j)| Point { i, j })
+        );
+        assert_eq!(
+            turnover,
+            Turnover {
+                births: 2,
+                deaths: 2
+            }
+        );
+        universe.tick();
+        assert_eq!(
+            universe.sorted_cells(),
+            [(-1, 0), (0, 0), (1, 0)].map(|(i, j)| Point { i, j })
+        );
+    }
+
+    #[test]
+    fn survivors_age_and_changes_are_reported() {
+        // A blinker's center survives, while its ends die and are reborn.
+        let mut universe = universe(&[(0, -1), (0, 0), (0, 1)]);
+        let mut changes = Vec::new();
+        for _ in 0..3 {
+            changes.clear();
+            universe.tick_with(|p, c| changes.push((p.i, p.j, c)));
+        }
+        let everywhere = universe.bounds().unwrap();
+        let mut ages: Vec<_> = universe.ages_within(everywhere).collect();
+        ages.sort_unstable_by_key(|&(p, _)| (p.i, p.j));
+        let ages: Vec<_> = ages.into_iter().map(|(_, age)| age).collect();
+        assert_eq!(ages, [0, 3, 0]);
+        changes.sort_unstable_by_key(|&(i, j, _)| (i, j));
+        assert_eq!(
+            changes,
+            [
+                (-1, 0, Cell::LIVE),
+                (0, -1, Cell::DEAD),
+                (0, 1, Cell::DEAD),
+                (1, 0, Cell::LIVE)
+            ]
+        );
+    }
+
+    #[test]
+    fn electrons_flow_along_wires_across_tile_edges() {
+        let mut universe = Universe::new();
+        universe.set_rule(Rule::Wireworld);
+        for j in -20..20 {
+            universe.set(Point { i: 0, j }, Rule::WIREWORLD_CONDUCTOR);
+        }
+        universe.set(Point { i: 0, j: -20 }, Rule::WIREWORLD_TAIL);
+        universe.set(Point { i: 0, j: -19 }, Rule::WIREWORLD_HEAD);
+        let turnover = universe.tick();
+        assert_eq!(
+            turnover,
+            Turnover {
+                births: 0,
+                deaths: 0
+            }
+        );
+        for _ in 1..30 {
+            universe.tick();
+        }
+        let at = |j| universe.at(Point { i: 0, j });
+        assert_eq!(at(10), Rule::WIREWORLD_TAIL);
+        assert_eq!(at(11), Rule::WIREWORLD_HEAD);
+        assert_eq!(at(12), Rule::WIREWORLD_CONDUCTOR);
+        assert_eq!(universe.population(), 40);
+    }
+
+    #[test]
+    fn dying_cells_block_births() {
+        // In Brian's Brain, a domino fires into the cells beside it, and its
+        // dying cells keep the new ones from turning back.
+        let mut universe = universe(&[(0, 0), (0, 1)]);
+        universe.set_rule(Rule::BRIANS_BRAIN);
+        universe.tick();
+        assert_eq!(universe.at(Point { i: 0, j: 0 }), Cell(2));
+        assert_eq!(universe.at(Point { i: -1, j: 0 }), Cell::LIVE);
+        assert_eq!(universe.population(), 6);
+        universe.tick();
+        assert_eq!(universe.at(Point { i: 0, j: 0 }), Cell::DEAD);
+        assert_eq!(universe.at(Point { i: -1, j: 0 }), Cell(2));
+    }
+
+    #[test]
+    fn other_grids_count_their_own_neighbors() {
+        // Compare against a direct count of each cell's neighbors, for a soup
+        // spanning several tiles.
+        for rule in ["B2/S34H", "B45/S34L", "B3/S23/C4L"] {
+            let rule: Rule = rule.parse().unwrap();
+            let mut universe = Soup::default().generate();
+            universe.set_rule(rule);
+            for _ in 0..8 {
+                let mut expected = Vec::new();
+                let bounds = universe.bounds().unwrap();
+                for i in bounds.min.i - 2..=bounds.max.i + 2 {
+                    for j in bounds.min.j - 2..=bounds.max.j + 2 {
+                        let p = Point { i, j };
+                        let neighbors = rule.grid().neighbors(p).iter();
+                        let n = neighbors
+                            .filter(|&&(di, dj)| {
+                                universe.at(Point {
+                                    i: i + di,
+                                    j: j + dj,
+                                }) == Cell::LIVE
+                            })
+                            .count();
+                        let c = rule.next(universe.at(p), u16::try_from(n).unwrap());
+                        if c != Cell::DEAD {
+                            expected.push((i, j, c));
+                        }
+                    }
+                }
+                universe.tick();
+                let mut actual: Vec<_> = universe.states().map(|(p, c)| (p.i, p.j, c)).collect();
+                actual.sort_unstable_by_key(|&(i, j, _)| (i, j));
+                assert_eq!(actual, expected, "{rule}");
+            }
+        }
+    }
+
+    #[test]
+    fn larger_than_life_counts_far_neighbors() {
+        // Compare against a direct count over each neighborhood, for soups
+        // spanning several tiles, on the plane and on a torus smaller than the
+        // windows are wide. Each neighborhood here counts the middle cell
+        // itself, except the diamond.
+        type Within = fn(i32, i32) -> bool;
+        let moore = |_: i32, _: i32| true;
+        let diamond = |di: i32, dj: i32| (1..=4).contains(&(di.abs() + dj.abs()));
+        let circle = |di: i32, dj: i32| di * di + dj * dj <= 10 * 10 + 10;
+        let cases: [(&str, i32, Within); 3] = [
+            ("R5,C0,M1,S34..58,B34..45,NM", 5, moore),
+            ("R4,C3,M0,S8..14,B9..12,NN", 4, diamond),
+            ("R10,C0,M1,S120..220,B125..170,NC", 10, circle),
+        ];
+        for topology in ["", "T40,36"] {
+            for &(rule, range, within) in &cases {
+                let rule: Rule = rule.parse().unwrap();
+                let mut universe = Soup {
+                    size: 40,
+                    ..Soup::default()
+                }
+                .generate();
+                universe.set_topology(topology.parse().unwrap());
+                universe.set_rule(rule);
+                for _ in 0..3 {
+                    let mut expected = Vec::new();
+                    let bounds = universe.topology().bounds();
+                    let bounds = bounds.or(universe.bounds()).unwrap();
+                    let reach = if topology.is_empty() { range } else { 0 };
+                    for i in bounds.min.i - reach..=bounds.max.i + reach {
+                        for j in bounds.min.j - reach..=bounds.max.j + reach {
+                            let mut n = 0;
+                            for di in -range..=range {
+                                for dj in -range..=range {
+                                    let q = Point {
+                                        i: i + di,
+                                        j: j + dj,
+                                    };
+                                    if within(di, dj) && universe.at(q) == Cell::LIVE {
+                                        n += 1;
+                                    }
+                                }
+                            }
+                            let c = rule.next(universe.at(Point { i, j }), n);
+                            if c != Cell::DEAD {
+                                expected.push((i, j, c));
+                            }
+                        }
+                    }
+                    universe.tick();
+                    let mut actual: Vec<_> =
+                        universe.states().map(|(p, c)| (p.i, p.j, c)).collect();
+                    actual.sort_unstable_by_key(|&(i, j, _)| (i, j));
+                    assert_eq!(actual, expected, "{rule} {topology}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn conway_as_larger_than_life() {
+        let mut life = Soup::default().generate();
+        let mut larger = life.clone();
+        larger.set_rule("R1,C0,M0,S2..3,B3..3,NM".parse().unwrap());
+        for _ in 0..20 {
+            life.tick();
+            larger.tick();
+        }
+        assert_eq!(larger.sorted_cells(), life.sorted_cells());
+    }
+
+    #[test]
+    fn glider_travels_without_bounds() {
+        let mut universe = universe(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+        for _ in 0..4 * 100 {
+            universe.tick();
+        }
+        let bounds = universe.bounds().unwrap();
+        assert_eq!(bounds.min, Point { i: 100, j: 100 });
+        assert_eq!(universe.population(), 5);
+        assert_eq!(universe.tiles.len(), 1);
+    }
+
+    #[test]
+    fn blinker_wraps_around_a_torus() {
+        let mut universe = universe(&[]);
+        universe.set_topology(Topology::Torus(SizeU32 {
+            width: 5,
+            height: 5,
+        }));
+        for (i, j) in [(2, 4), (2, 0), (2, 1)] {
+            universe.set(Point { i, j }, Cell::LIVE);
+        }
+        let turnover = universe.tick();
+        assert_eq!(
+            universe.sorted_cells(),
+            [(1, 0), (2, 0), (3, 0)].map(|(i, j)| Point { i, j })
+        );
+        assert_eq!(
+            turnover,
+            Turnover {
+                births: 2,
+                deaths: 2
             }
+        );
+    }
+
+    #[test]
+    fn glider_circles_a_torus() {
+        // Not a multiple of the tile size, so that tiles straddle the edges.
+        let size = SizeU32 {
+            width: 40,
+            height: 40,
+        };
+        let glider = [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+        let mut torus = universe(&glider);
+        torus.set_topology(Topology::Torus(size));
+        for _ in 0..4 * 40 {
+            torus.tick();
         }
+        assert_eq!(torus.sorted_cells(), universe(&glider).sorted_cells());
+    }
+
+    #[test]
+    fn resizing_a_torus_keeps_the_pattern_centered() {
+        let mut universe = universe(&[(0, 0), (5, 5), (5, 6)]);
+        universe.set_topology(Topology::Torus(SizeU32 {
+            width: 10,
+            height: 10,
+        }));
+        universe.set_topology(Topology::Torus(SizeU32 {
+            width: 6,
+            height: 6,
+        }));
+        assert_eq!(
+            universe.sorted_cells(),
+            [(3, 3), (3, 4)].map(|(i, j)| Point { i, j })
+        );
+        universe.set_topology(Topology::Torus(SizeU32 {
+            width: 10,
+            height: 10,
+        }));
+        assert_eq!(
+            universe.sorted_cells(),
+            [(5, 5), (5, 6)].map(|(i, j)| Point { i, j })
+        );
     }
 }
@@ -0,0 +1,148 @@
+//! Mapping between universe cells and canvas pixels.
+
+use system::{SizeU32, f64_to_i32_saturating};
+
+use crate::universe::{Bounds, Point};
+
+/// Canvas pixels per cell, at the most distant zoom level.
+pub const MIN_ZOOM: f64 = 1.0 / 16.0;
+/// Canvas pixels per cell, at the closest zoom level.
+pub const MAX_ZOOM: f64 = 32.0;
+
+pub struct Viewport {
+    /// Universe coordinates of the top left corner of the canvas, measured in
+    /// cells, as column (`x`) and row (`y`).
+    x: f64,
+    y: f64,
+    /// Canvas pixels per cell.
+    zoom: f64,
+}
+
+impl Viewport {
+    /// Returns a viewport with the origin of the universe at the top left
+    /// corner of the canvas.
+    pub fn new(zoom: f64) -> Viewport {
+        Viewport {
+            x: 0.0,
+            y: 0.0,
+            zoom: zoom.clamp(MIN_ZOOM, MAX_ZOOM),
+        }
+    }
+
+    /// Returns the number of canvas pixels per cell.
+    pub fn zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    /// Returns the universe coordinates `(x, y)`, in cells, of the specified
+    /// canvas pixel.
+    pub fn position(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (self.x + x / self.zoom, self.y + y / self.zoom)
+    }
+
+    /// Returns the canvas pixel at the specified universe coordinates. The
+    /// inverse of [`Self::position`].
+    pub fn pixel(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        ((x - self.x) * self.zoom, (y - self.y) * self.zoom)
+    }
+
+    /// Returns the square cell drawn at the specified canvas pixel.
+    pub fn cell_at(&self, pixel: (f64, f64)) -> Point {
+        let (x, y) = self.position(pixel);
+        Point {
+            i: f64_to_i32_saturating(y.floor()),
+            j: f64_to_i32_saturating(x.floor()),
+        }
+    }
+
+    /// Returns the canvas rectangle `(x, y, width, height)` in which the
+    /// specified cell is drawn. Edges are rounded to whole pixels so that
+    /// adjacent cells neither overlap nor leave gaps, and the rectangle is at
+    /// least one pixel on a side so that cells remain visible when zoomed out.
+    pub fn rect(&self, p: Point) -> (f64, f64, f64, f64) {
+        let x = ((f64::from(p.j) - self.x) * self.zoom).round();
+        let y = ((f64::from(p.i) - self.y) * self.zoom).round();
+        let right = ((f64::from(p.j) + 1.0 - self.x) * self.zoom).round();
+        let bottom = ((f64::from(p.i) + 1.0 - self.y) * self.zoom).round();
+        (x, y, (right - x).max(1.0), (bottom - y).max(1.0))
+    }
+
+    /// Returns the cells at least partly visible on a canvas of the specified
+    /// size.
+    pub fn visible(&self, canvas: SizeU32) -> Bounds {
+        Bounds {
+            min: self.cell_at((0.0, 0.0)),
+            max: self.cell_at((f64::from(canvas.width), f64::from(canvas.height))),
+        }
+    }
+
+    /// Moves the universe by the specified number of canvas pixels.
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        self.x -= dx / self.zoom;
+        self.y -= dy / self.zoom;
+    }
+
+    /// Multiplies the zoom level by `factor`, within limits, keeping the cell
+    /// under the specified canvas pixel in place.
+    pub fn zoom_about(&mut self, factor: f64, (x, y): (f64, f64)) {
+        let zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.x += x / self.zoom - x / zoom;
+        self.y += y / self.zoom - y / zoom;
+        self.zoom = zoom;
+    }
+
+    /// Zooms and pans so that the specified cells fill as much of the canvas
+    /// as possible, centered.
+    pub fn fit(&mut self, bounds: Bounds, canvas: SizeU32) {
+        let size = bounds.size();
+        let (width, height) = (f64::from(canvas.width), f64::from(canvas.height));
+        self.zoom = (width / f64::from(size.width))
+            .min(height / f64::from(size.height))
+            .clamp(MIN_ZOOM, MAX_ZOOM);
+        self.center_on(bounds, canvas);
+    }
+
+    /// Pans, without zooming, so that the specified cells are centered on the
+    /// canvas.
+    pub fn center_on(&mut self, bounds: Bounds, canvas: SizeU32) {
+        let size = bounds.size();
+        let (width, height) = (f64::from(canvas.width), f64::from(canvas.height));
+        self.x = f64::from(bounds.min.j) + (f64::from(size.width) - width / self.zoom) / 2.0;
+        self.y = f64::from(bounds.min.i) + (f64::from(size.height) - height / self.zoom) / 2.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zoom_keeps_the_cell_under_the_pointer() {
+        let mut viewport = Viewport::new(2.0);
+        viewport.pan(-13.0, 7.0);
+        let pointer = (100.0, 58.0);
+        let cell = viewport.cell_at(pointer);
+        for factor in [2.0, 3.5, 0.25, 0.1] {
+            viewport.zoom_about(factor, pointer);
+            assert_eq!(viewport.cell_at(pointer), cell);
+        }
+    }
+
+    #[test]
+    fn fit_centers_the_bounds() {
+        let mut viewport = Viewport::new(1.0);
+        let bounds = Bounds {
+            min: Point { i: -10, j: 5 },
+            max: Point { i: 9, j: 14 },
+        };
+        viewport.fit(
+            bounds,
+            SizeU32 {
+                width: 200,
+                height: 100,
+            },
+        );
+        assert!((viewport.zoom - 5.0).abs() < f64::EPSILON);
+        assert_eq!(viewport.cell_at((100.0, 50.0)), Point { i: 0, j: 10 });
+    }
+}
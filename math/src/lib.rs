@@ -23,7 +23,7 @@ impl LinearCongruentialGenerator {
     }
 
     pub fn next_bool(&mut self) -> bool {
-        self.next_u32() % 2 != 0
+        !self.next_u32().is_multiple_of(2)
     }
 
     #[expect(clippy::cast_possible_wrap)]
@@ -0,0 +1,113 @@
+//! Controls for moving through the numbers: how fast they go by, and jumping
+//! or stepping to a particular one.
+
+use web_sys::{Element, HtmlInputElement};
+
+use easel::Result;
+use sugar::prelude::*;
+use system::{System, f64_to_u32_saturating};
+
+/// Numbers shown per second when the page loads, which matches a typical
+/// display refresh rate.
+const DEFAULT_SPEED: u32 = 60;
+
+const MAX_SPEED: u32 = 240;
+
+/// The largest number that can be jumped to. Factoring a number grows the
+/// sieve up to its largest prime factor, which would hang the page for much
+/// larger primes.
+const MAX_TARGET: u32 = 1_000_000;
+
+pub struct Navigation {
+    pub root: Element,
+    speed: HtmlInputElement,
+    /// Shows the speed chosen with the slider.
+    rate: Element,
+    target: HtmlInputElement,
+    pub back: Element,
+    pub forward: Element,
+}
+
+impl Navigation {
+    /// # Errors
+    ///
+    /// Will return [`Err`] if DOM interaction fails.
+    pub fn new(system: &System) -> Result<Self> {
+        let speed = INPUT
+            .class("primes-speed")
+            .attr("type", "range")
+            .attr("min", "1")
+            .attr("title", "Numbers per second")
+            .to_element(system)?;
+        let rate = SPAN.class("primes-speed__rate").to_element(system)?;
+        let target = INPUT
+            .class("primes-target")
+            .attr("type", "number")
+            .attr("min", "1")
+            .attr("placeholder", "Go to n")
+            .attr("title", "Go to a number")
+            .to_element(system)?;
+        let back = BUTTON
+            .attr("title", "Previous number")
+            .text("◀")
+            .to_element(system)?;
+        let forward = BUTTON
+            .attr("title", "Next number")
+            .text("▶")
+            .to_element(system)?;
+        let root = DIV
+            .class("primes-controls")
+            .child3(
+                DIV.class("primes-controls__group").child2(&speed, &rate),
+                &target,
+                DIV.class("primes-controls__group").child2(&back, &forward),
+            )
+            .to_element(system)?;
+        let navigation = Navigation {
+            root,
+            speed: speed.dyn_cast()?,
+            rate,
+            target: target.dyn_cast()?,
+            back,
+            forward,
+        };
+        navigation.speed.set_max(&MAX_SPEED.to_string());
+        navigation
+            .speed
+            .set_value_as_number(f64::from(DEFAULT_SPEED));
+        navigation.target.set_max(&MAX_TARGET.to_string());
+        navigation.show_speed();
+        Ok(navigation)
+    }
+
+    pub fn speed_input(&self) -> &HtmlInputElement {
+        &self.speed
+    }
+
+    pub fn target_input(&self) -> &HtmlInputElement {
+        &self.target
+    }
+
+    /// Returns the numbers to show per second.
+    pub fn speed(&self) -> f64 {
+        let speed = self.speed.value_as_number();
+        if speed.is_finite() {
+            speed
+        } else {
+            f64::from(DEFAULT_SPEED)
+        }
+    }
+
+    /// Updates the label beside the slider to match it.
+    pub fn show_speed(&self) {
+        let rate = format!("{}/s", self.speed());
+        self.rate.set_text_content(Some(&rate));
+    }
+
+    /// Returns the number to go to, if one has been entered, clamped to the
+    /// largest number allowed.
+    pub fn target(&self) -> Option<u32> {
+        let target = self.target.value_as_number();
+        (target.is_finite() && target >= 1.0).then(|| f64_to_u32_saturating(target).min(MAX_TARGET))
+    }
+}
@@ -0,0 +1,194 @@
+//! Number-theoretic facts about a number, derived from its factorization.
+
+use std::fmt;
+
+/// Writes `n` in superscript digits.
+fn superscript(n: u32) -> String {
+    const DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+    n.to_string()
+        .bytes()
+        .map(|digit| DIGITS[usize::from(digit - b'0')])
+        .collect()
+}
+
+/// A positive number, and the facts that follow from its prime factorization.
+pub struct Facts {
+    value: u32,
+    /// The distinct prime factors, each with its exponent, in ascending order.
+    factors: Vec<(u32, u32)>,
+}
+
+impl Facts {
+    /// Collects facts about `value`, given each of its prime factors along
+    /// with a positive exponent.
+    pub fn new(value: u32, factors: impl IntoIterator<Item = (u32, u32)>) -> Facts {
+        Facts {
+            value,
+            factors: factors.into_iter().collect(),
+        }
+    }
+
+    /// Returns how many numbers divide this one, including 1 and itself.
+    pub fn divisor_count(&self) -> u64 {
+        self.factors
+            .iter()
+            .map(|&(_, e)| u64::from(e) + 1)
+            .product()
+    }
+
+    /// Returns σ, the sum of all divisors, including 1 and the number itself.
+    pub fn divisor_sum(&self) -> u64 {
+        self.factors
+            .iter()
+            .map(|&(p, e)| {
+                // 1 + p + p² + … + pᵉ
+                let p = u64::from(p);
+                (0..e).fold(1, |sum, _| sum * p + 1)
+            })
+            .product()
+    }
+
+    /// Returns Euler's totient φ, the count of numbers up to this one that are
+    /// coprime to it.
+    pub fn totient(&self) -> u32 {
+        self.factors
+            .iter()
+            .map(|&(p, e)| p.pow(e - 1) * (p - 1))
+            .product()
+    }
+
+    /// Returns the Möbius function μ: zero if any square divides the number,
+    /// and otherwise 1 or -1 as the number of prime factors is even or odd.
+    pub fn mobius(&self) -> i8 {
+        if !self.is_square_free() {
+            0
+        } else if self.factors.len().is_multiple_of(2) {
+            1
+        } else {
+            -1
+        }
+    }
+
+    pub fn is_square_free(&self) -> bool {
+        self.factors.iter().all(|&(_, e)| e == 1)
+    }
+
+    pub fn is_prime(&self) -> bool {
+        matches!(self.factors.as_slice(), [(_, 1)])
+    }
+
+    pub fn is_prime_power(&self) -> bool {
+        self.factors.len() == 1
+    }
+
+    /// Returns whether the number equals the sum of its proper divisors.
+    pub fn is_perfect(&self) -> bool {
+        self.divisor_sum() == 2 * u64::from(self.value)
+    }
+
+    /// Returns whether the sum of the number's proper divisors exceeds it.
+    pub fn is_abundant(&self) -> bool {
+        self.divisor_sum() > 2 * u64::from(self.value)
+    }
+
+    /// Returns the names of the notable properties of this number.
+    fn properties(&self) -> Vec<&'static str> {
+        let mut properties = Vec::new();
+        if self.is_prime() {
+            properties.push("prime");
+        } else if self.is_prime_power() {
+            properties.push("prime power");
+        }
+        if self.is_square_free() {
+            properties.push("square-free");
+        }
+        if self.is_perfect() {
+            properties.push("perfect");
+        } else if self.is_abundant() {
+            properties.push("abundant");
+        }
+        properties
+    }
+}
+
+/// Writes the number as a product of prime powers, such as `360 = 2³ · 3² · 5`,
+/// followed by its divisor functions and properties.
+impl fmt::Display for Facts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)?;
+        if !self.is_prime() {
+            for (index, &(p, e)) in self.factors.iter().enumerate() {
+                f.write_str(if index == 0 { " = " } else { " · " })?;
+                write!(f, "{p}")?;
+                if e > 1 {
+                    f.write_str(&superscript(e))?;
+                }
+            }
+        }
+        let count = self.divisor_count();
+        let noun = if count == 1 { "divisor" } else { "divisors" };
+        write!(
+            f,
+            "; {count} {noun} summing to {}; φ = {}; μ = {}",
+            self.divisor_sum(),
+            self.totient(),
+            self.mobius(),
+        )?;
+        let properties = self.properties();
+        if !properties.is_empty() {
+            write!(f, "; {}", properties.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn superscripts() {
+        assert_eq!(superscript(0), "⁰");
+        assert_eq!(superscript(31), "³¹");
+    }
+
+    #[test]
+    fn facts() {
+        let facts = Facts::new(360, [(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(
+            facts.to_string(),
+            "360 = 2³ · 3² · 5; 24 divisors summing to 1170; φ = 96; μ = 0; abundant"
+        );
+        assert_eq!(
+            Facts::new(1, []).to_string(),
+            "1; 1 divisor summing to 1; φ = 1; μ = 1; square-free"
+        );
+        assert_eq!(
+            Facts::new(7, [(7, 1)]).to_string(),
+            "7; 2 divisors summing to 8; φ = 6; μ = -1; prime, square-free"
+        );
+        assert_eq!(
+            Facts::new(8, [(2, 3)]).to_string(),
+            "8 = 2³; 4 divisors summing to 15; φ = 4; μ = 0; prime power"
+        );
+        assert_eq!(
+            Facts::new(28, [(2, 2), (7, 1)]).to_string(),
+            "28 = 2² · 7; 6 divisors summing to 56; φ = 12; μ = 0; perfect"
+        );
+        assert_eq!(
+            Facts::new(30, [(2, 1), (3, 1), (5, 1)]).to_string(),
+            "30 = 2 · 3 · 5; 8 divisors summing to 72; φ = 8; μ = -1; square-free, abundant"
+        );
+    }
+
+    #[test]
+    fn largest_values_do_not_overflow() {
+        // The largest power of two, and then the largest prime, below 2³².
+        let facts = Facts::new(1 << 31, [(2, 31)]);
+        assert_eq!(facts.divisor_sum(), (1 << 32) - 1);
+        assert_eq!(facts.totient(), 1 << 30);
+        let facts = Facts::new(u32::MAX - 4, [(u32::MAX - 4, 1)]);
+        assert_eq!(facts.divisor_sum(), u64::from(u32::MAX) - 3);
+        assert_eq!(facts.totient(), u32::MAX - 5);
+    }
+}
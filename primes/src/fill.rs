@@ -1,3 +1,5 @@
+use std::fmt;
+
 const COLORS: [&str; 14] = [
     "#FF0000", //  2,  47 Red
     "#00FF00", //  3,  53 Lime
@@ -15,21 +17,82 @@ const COLORS: [&str; 14] = [
     "#000080", // 43, 107 Navy
 ];
 
-/// The fill style is currently hard-coded, so the compiler helpfully points out
-/// that only one of the variants is actually in use for any given build. An
-/// alternative to suppressing the lint would be to allow changes at runtime.
-#[allow(dead_code)]
-#[derive(Clone, Copy)]
+/// Okabe and Ito's palette, which stays distinct under the common forms of
+/// color blindness. Its black is left out, being invisible on the page.
+const COLORBLIND: [&str; 7] = [
+    "#E69F00", // Orange
+    "#56B4E9", // Sky blue
+    "#009E73", // Bluish green
+    "#F0E442", // Yellow
+    "#0072B2", // Blue
+    "#D55E00", // Vermillion
+    "#CC79A7", // Reddish purple
+];
+
+/// Evenly spaced stops along matplotlib's viridis color map, from dark purple
+/// to yellow, so that lightness rises steadily with the prime.
+const VIRIDIS: [&str; 10] = [
+    "#440154", "#482878", "#3E4A89", "#31688E", "#26828E", "#1F9E89", "#35B779", "#6DCD59",
+    "#B4DE2C", "#FDE725",
+];
+
+/// Degrees between the hues of consecutive pastel colors: the golden angle, so
+/// that nearby columns never share a hue.
+const GOLDEN_ANGLE: usize = 137;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum FillStyle {
+    #[default]
     Color,
     Grayscale,
+    Colorblind,
+    Viridis,
+    Pastel,
 }
 
 impl FillStyle {
+    pub const ALL: [FillStyle; 5] = [
+        FillStyle::Color,
+        FillStyle::Grayscale,
+        FillStyle::Colorblind,
+        FillStyle::Viridis,
+        FillStyle::Pastel,
+    ];
+
+    /// Returns the name under which the style is saved.
+    pub fn key(self) -> &'static str {
+        match self {
+            FillStyle::Color => "color",
+            FillStyle::Grayscale => "grayscale",
+            FillStyle::Colorblind => "colorblind",
+            FillStyle::Viridis => "viridis",
+            FillStyle::Pastel => "pastel",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<FillStyle> {
+        FillStyle::ALL.into_iter().find(|style| style.key() == key)
+    }
+
     pub fn get(self, index: usize) -> String {
         match self {
             FillStyle::Color => COLORS[index % COLORS.len()].to_owned(),
             FillStyle::Grayscale => format!("#{i:02x}{i:02x}{i:02x}", i = 15 + index % 16 * 14),
+            FillStyle::Colorblind => COLORBLIND[index % COLORBLIND.len()].to_owned(),
+            FillStyle::Viridis => VIRIDIS[index % VIRIDIS.len()].to_owned(),
+            FillStyle::Pastel => format!("hsl({}, 70%, 80%)", index * GOLDEN_ANGLE % 360),
         }
     }
 }
+
+impl fmt::Display for FillStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FillStyle::Color => "Color",
+            FillStyle::Grayscale => "Grayscale",
+            FillStyle::Colorblind => "Colorblind-safe",
+            FillStyle::Viridis => "Viridis",
+            FillStyle::Pastel => "Pastel",
+        })
+    }
+}
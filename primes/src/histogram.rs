@@ -1,29 +1,78 @@
+use std::fmt;
+
 use easel::canvas_size;
 use rk_primes::Sieve;
-use system::{SizeU32, usize_to_u32};
+use system::{SizeU32, f64_to_u32_saturating, u32_to_usize, usize_to_u32};
 use web_sys::CanvasRenderingContext2d;
 
 use crate::fill::FillStyle;
 
-const GAP: u32 = 2;
+const GAP: f64 = 2.0;
+
+/// The narrowest a column may be on the full axis. Columns that don't fit are
+/// scrolled out of view.
+const MIN_WIDTH: f64 = 12.0;
+
+/// The width of a skipped run of zero exponents on the compressed axis,
+/// relative to that of a column.
+const SKIP: f64 = 0.25;
 
 /// Replaces `powers` with the exponents of all prime factors in `n`, including
-/// zeroes, up to the maximum prime factor.
-fn prime_factor(sieve: &mut Sieve, powers: &mut Vec<u32>, mut n: u32) {
+/// zeroes, up to the maximum prime factor, and `primes` with those factors.
+fn prime_factor(sieve: &mut Sieve, primes: &mut Vec<u32>, powers: &mut Vec<u32>, mut n: u32) {
+    primes.clear();
     powers.clear();
     for p in sieve.primes() {
         if n < 2 {
             break;
         }
         let mut e = 0;
-        while n % p == 0 {
+        while n.is_multiple_of(p) {
             n /= p;
             e += 1;
         }
+        primes.push(p);
         powers.push(e);
     }
 }
 
+/// How the primes are spaced along the horizontal axis.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Axis {
+    /// Only primes that divide the number get columns, so that the columns
+    /// stay wide however large the prime factors.
+    #[default]
+    Compressed,
+    /// Every prime up to the largest factor gets a column, scrolling when
+    /// they don't all fit.
+    Full,
+}
+
+impl Axis {
+    pub const ALL: [Axis; 2] = [Axis::Compressed, Axis::Full];
+
+    /// Returns the name under which the axis is saved.
+    pub fn key(self) -> &'static str {
+        match self {
+            Axis::Compressed => "compressed",
+            Axis::Full => "full",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Axis> {
+        Axis::ALL.into_iter().find(|axis| axis.key() == key)
+    }
+}
+
+impl fmt::Display for Axis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Axis::Compressed => "Factors only",
+            Axis::Full => "All primes",
+        })
+    }
+}
+
 struct Rectangle {
     x: f64,
     y: f64,
@@ -31,64 +80,54 @@ struct Rectangle {
     h: f64,
 }
 
-struct Columns<'a> {
-    /// The exponents represented by these columns.
-    powers: &'a [u32],
-    /// The size of each brick.
-    brick: SizeU32,
-    /// The index in [`Self::powers`] of the next column to return.
+/// A column of bricks, one brick per power of a prime.
+struct Column {
+    /// The index of the prime among all primes, and of its exponent in
+    /// [`Histogram::powers`].
     index: usize,
+    x: f64,
+    width: f64,
 }
 
-impl Columns<'_> {
-    fn new(powers: &[u32], canvas: SizeU32) -> Columns {
-        let max_power = powers.iter().copied().max().unwrap_or(1);
-        Columns {
-            powers,
-            brick: SizeU32 {
-                height: canvas.height.checked_div(max_power).unwrap_or_default(),
-                width: canvas
-                    .width
-                    .checked_div(usize_to_u32(powers.len()))
-                    .unwrap_or_default(),
-            },
-            index: 0,
-        }
-    }
-}
-
-impl Iterator for Columns<'_> {
-    type Item = Vec<Rectangle>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let length = self.powers.len();
-        if self.index == length {
-            return None;
-        }
-        let x = usize_to_u32(self.index) * self.brick.width;
-        let rects = (0..self.powers[self.index])
-            .map(|p| Rectangle {
-                x: f64::from(x + GAP),
-                y: f64::from(p * self.brick.height + GAP),
-                w: f64::from(self.brick.width.saturating_sub(GAP * 2)),
-                h: f64::from(self.brick.height.saturating_sub(GAP * 2)),
-            })
-            .collect();
-        self.index += 1;
-        Some(rects)
+/// Returns columns spanning the specified widths, in arbitrary units, scaled
+/// to fill the canvas width. Edges are rounded to whole pixels, so that
+/// clearing a column erases it completely.
+fn scale(slots: &[(usize, f64, f64)], total: f64, canvas_width: f64) -> Vec<Column> {
+    if total <= 0.0 {
+        return Vec::new();
     }
+    let unit = canvas_width / total;
+    slots
+        .iter()
+        .map(|&(index, start, width)| {
+            let x = (start * unit).floor();
+            Column {
+                index,
+                x,
+                width: ((start + width) * unit).floor() - x,
+            }
+        })
+        .collect()
 }
 
 pub struct Histogram {
+    primes: Vec<u32>,
     powers: Vec<u32>,
     value: u32,
+    axis: Axis,
+    /// How far the full axis is scrolled, from 0 (showing the smallest
+    /// primes) to 1 (showing the largest).
+    pan: f64,
 }
 
 impl Histogram {
     pub fn new() -> Self {
         Histogram {
+            primes: Vec::new(),
             powers: Vec::new(),
             value: 1,
+            axis: Axis::default(),
+            pan: 0.0,
         }
     }
 
@@ -96,19 +135,121 @@ impl Histogram {
         self.value
     }
 
+    pub fn set_axis(&mut self, axis: Axis) {
+        self.axis = axis;
+    }
+
+    /// Scrolls the full axis to the specified fraction of its length.
+    pub fn set_pan(&mut self, pan: f64) {
+        self.pan = pan.clamp(0.0, 1.0);
+    }
+
     pub fn incr(&mut self, sieve: &mut Sieve) -> &[u32] {
-        self.value += 1;
-        prime_factor(sieve, &mut self.powers, self.value);
+        self.set(sieve, self.value.saturating_add(1))
+    }
+
+    /// Moves back to the previous number, stopping at 1.
+    pub fn decr(&mut self, sieve: &mut Sieve) -> &[u32] {
+        self.set(sieve, self.value.saturating_sub(1))
+    }
+
+    /// Jumps to any positive number. Zero, which has no factorization, is
+    /// treated as 1.
+    pub fn set(&mut self, sieve: &mut Sieve, value: u32) -> &[u32] {
+        self.value = value.max(1);
+        prime_factor(sieve, &mut self.primes, &mut self.powers, self.value);
         &self.powers
     }
 
+    /// Returns each prime factor of the current number, with its exponent.
+    pub fn factors(&self) -> impl Iterator<Item = (u32, u32)> {
+        self.primes
+            .iter()
+            .copied()
+            .zip(self.powers.iter().copied())
+            .filter(|&(_, power)| power > 0)
+    }
+
+    /// Returns a column for each prime that divides the current number, with
+    /// a narrow space for each run of primes that don't.
+    fn compressed(&self, width: f64) -> Vec<Column> {
+        let mut slots = Vec::new();
+        let mut total = 0.0;
+        let mut skipping = false;
+        for (index, &power) in self.powers.iter().enumerate() {
+            if power > 0 {
+                slots.push((index, total, 1.0));
+                total += 1.0;
+                skipping = false;
+            } else if !skipping {
+                total += SKIP;
+                skipping = true;
+            }
+        }
+        scale(&slots, total, width)
+    }
+
+    /// Returns a column for each prime up to the largest factor of the current
+    /// number, or for as many of them as fit, starting at the pan position.
+    fn full(&self, width: f64) -> Vec<Column> {
+        let length = self.powers.len();
+        let fit = u32_to_usize(f64_to_u32_saturating(width / MIN_WIDTH)).max(1);
+        let visible = length.min(fit);
+        let hidden = f64::from(usize_to_u32(length - visible));
+        let first = u32_to_usize(f64_to_u32_saturating((self.pan * hidden).round()));
+        let slots: Vec<_> = (0..visible)
+            .map(|offset| (first + offset, f64::from(usize_to_u32(offset)), 1.0))
+            .collect();
+        scale(&slots, f64::from(usize_to_u32(visible)), width)
+    }
+
+    fn columns(&self, canvas: SizeU32) -> Vec<Column> {
+        let width = f64::from(canvas.width);
+        match self.axis {
+            Axis::Compressed => self.compressed(width),
+            Axis::Full => self.full(width),
+        }
+    }
+
+    /// Returns the bricks in view, each with the index of its prime.
+    fn bricks(&self, canvas: SizeU32) -> impl Iterator<Item = (usize, Rectangle)> {
+        let max_power = self.powers.iter().copied().max().unwrap_or(1);
+        let height = f64::from(canvas.height.checked_div(max_power).unwrap_or_default());
+        self.columns(canvas).into_iter().flat_map(move |column| {
+            (0..self.powers[column.index]).map(move |p| {
+                let brick = Rectangle {
+                    x: column.x + GAP,
+                    y: f64::from(p) * height + GAP,
+                    w: (column.width - GAP * 2.0).max(0.0),
+                    h: (height - GAP * 2.0).max(0.0),
+                };
+                (column.index, brick)
+            })
+        })
+    }
+
+    /// Describes which primes have columns in view, if any.
+    pub fn in_view(&self, canvas: SizeU32) -> Option<String> {
+        let columns = self.columns(canvas);
+        let primes: Vec<_> = columns.iter().map(|c| self.primes[c.index]).collect();
+        match (self.axis, primes.as_slice()) {
+            (_, []) => None,
+            (_, [prime]) => Some(format!("{prime} in view")),
+            (Axis::Full, [first, .., last]) => Some(format!("{first}–{last} in view")),
+            (Axis::Compressed, _) => {
+                let primes: Vec<_> = primes.iter().map(u32::to_string).collect();
+                Some(format!("{} in view", primes.join(", ")))
+            }
+        }
+    }
+
     /// Erases this histogram from the canvas.
     pub fn clear(&self, context: &CanvasRenderingContext2d) {
         let Some(canvas) = context.canvas() else {
             return;
         };
         context.begin_path();
-        for brick in Columns::new(&self.powers, canvas_size(&canvas)).flatten() {
+        for (_, brick) in self.bricks(canvas_size(&canvas)) {
             context.clear_rect(brick.x, brick.y, brick.w, brick.h);
         }
         context.stroke();
@@ -120,12 +261,57 @@ impl Histogram {
             return;
         };
         context.begin_path();
-        for (index, column) in Columns::new(&self.powers, canvas_size(&canvas)).enumerate() {
+        for (index, brick) in self.bricks(canvas_size(&canvas)) {
             context.set_fill_style_str(style.get(index).as_str());
-            for brick in column {
-                context.fill_rect(brick.x, brick.y, brick.w, brick.h);
-            }
+            context.fill_rect(brick.x, brick.y, brick.w, brick.h);
         }
         context.stroke();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CANVAS: SizeU32 = SizeU32 {
+        width: 300,
+        height: 150,
+    };
+
+    fn histogram(n: u32, axis: Axis) -> Histogram {
+        let mut histogram = Histogram::new();
+        histogram.set_axis(axis);
+        histogram.set(&mut Sieve::new(), n);
+        histogram
+    }
+
+    #[test]
+    fn compressed_axis_skips_primes_that_do_not_divide() {
+        // 2 × 65521, the largest prime below 2¹⁶.
+        let histogram = histogram(131_042, Axis::Compressed);
+        let columns = histogram.columns(CANVAS);
+        let primes: Vec<_> = columns.iter().map(|c| histogram.primes[c.index]).collect();
+        assert_eq!(primes, [2, 65521]);
+        assert!(columns.iter().all(|c| c.width > 100.0));
+        assert_eq!(
+            histogram.in_view(CANVAS).as_deref(),
+            Some("2, 65521 in view")
+        );
+    }
+
+    #[test]
+    fn full_axis_scrolls() {
+        let mut histogram = histogram(131_042, Axis::Full);
+        let fit = 300 / 12;
+        assert_eq!(histogram.in_view(CANVAS).as_deref(), Some("2–97 in view"));
+        assert_eq!(histogram.columns(CANVAS).len(), fit);
+        histogram.set_pan(1.0);
+        let columns = histogram.columns(CANVAS);
+        assert_eq!(columns.len(), fit);
+        assert_eq!(
+            columns.last().map(|c| c.index),
+            Some(histogram.powers.len() - 1)
+        );
+        assert!(columns.iter().all(|c| c.width >= MIN_WIDTH));
+    }
+}
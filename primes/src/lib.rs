@@ -7,22 +7,232 @@
 //!
 //! # TODO
 //!
-//! * [] Add settings, such as speed control and color selection, to the UI.
 //! * [] Add audio, such as mapping primes to piano keys.
 //! * [] Instead of a canvas, animate DOM elements with intuitive transitions.
 
+mod controls;
+mod facts;
 mod fill;
 mod histogram;
+mod settings;
+mod spiral;
+mod view;
 
-use web_sys::Element;
+use std::{cell::RefCell, rc::Rc};
 
-use easel::{Easel, RenderContext, Result};
+use rk_primes::Sieve;
+use web_sys::{CanvasRenderingContext2d, Element, Event};
+
+use controls::Navigation;
+use easel::{Easel, RenderContext, Result, canvas_size};
+use facts::Facts;
 use fill::FillStyle;
 use histogram::Histogram;
-use system::System;
+use settings::Settings;
+use sugar::{Listener, prelude::*};
+use system::{System, f64_to_u32_saturating};
+use view::View;
+
+/// State shared by the easel's render callback and the event handlers.
+struct State {
+    sieve: Sieve,
+    histogram: Histogram,
+    style: FillStyle,
+    view: View,
+    /// Numbers to show per second.
+    speed: f64,
+    /// Numbers due but not yet shown, including a fraction of the next.
+    owed: f64,
+}
+
+impl State {
+    /// Lets `update` change the current number, then draws the change. The
+    /// histogram shows only the current number, whereas spirals show every
+    /// number up to it.
+    fn change(
+        &mut self,
+        context: &CanvasRenderingContext2d,
+        caption: &Element,
+        update: impl FnOnce(&mut Self),
+    ) {
+        let old = self.histogram.value();
+        if self.view == View::Histogram {
+            self.histogram.clear(context);
+        }
+        update(self);
+        let new = self.histogram.value();
+        match self.view.spiral() {
+            None => self.histogram.fill(context, self.style),
+            Some(spiral) if new > old => spiral.draw(context, &mut self.sieve, old + 1..=new),
+            Some(spiral) => spiral.erase(context, new + 1..=old),
+        }
+        self.describe(context, caption);
+    }
+
+    /// Erases the canvas, and draws the current number in the current view.
+    fn redraw(&mut self, context: &CanvasRenderingContext2d, caption: &Element) {
+        if let Some(canvas) = context.canvas() {
+            let size = canvas_size(&canvas);
+            context.clear_rect(0.0, 0.0, size.width.into(), size.height.into());
+        }
+        let value = self.histogram.value();
+        match self.view.spiral() {
+            None => self.histogram.fill(context, self.style),
+            Some(spiral) => spiral.draw(context, &mut self.sieve, 1..=value),
+        }
+        self.describe(context, caption);
+    }
+
+    /// Describes the current number in the caption, along with which primes
+    /// the histogram shows.
+    fn describe(&self, context: &CanvasRenderingContext2d, caption: &Element) {
+        let facts = Facts::new(self.histogram.value(), self.histogram.factors());
+        let mut caption_text = facts.to_string();
+        let in_view = context
+            .canvas()
+            .filter(|_| self.view == View::Histogram)
+            .and_then(|canvas| self.histogram.in_view(canvas_size(&canvas)));
+        if let Some(in_view) = in_view {
+            caption_text.push_str("; ");
+            caption_text.push_str(&in_view);
+        }
+        caption.set_text_content(Some(&caption_text));
+    }
+
+    /// Shows the numbers due over the specified elapsed time, skipping to the
+    /// last of them. At most a second's worth accrue, so that the chart
+    /// doesn't leap ahead after the page has been hidden.
+    fn advance(&mut self, context: &CanvasRenderingContext2d, caption: &Element, delta_ms: f64) {
+        self.owed = (self.owed + self.speed * delta_ms / 1000.0).min(self.speed.max(1.0));
+        let due = f64_to_u32_saturating(self.owed.floor());
+        if due == 0 {
+            return;
+        }
+        self.owed -= f64::from(due);
+        let value = self.histogram.value().saturating_add(due);
+        self.change(context, caption, |state| {
+            state.histogram.set(&mut state.sieve, value);
+        });
+    }
+}
+
+/// A change to the state, drawn to the specified canvas and caption.
+type Paint<'a> = &'a dyn Fn(&mut State, &CanvasRenderingContext2d, &Element);
+
+/// Returns a function that paints changes to the state, whether or not the
+/// easel is paused.
+fn painter(
+    state: &Rc<RefCell<State>>,
+    easel: &Rc<RefCell<Easel>>,
+) -> impl Fn(Paint) + Clone + 'static {
+    let (state, easel) = (Rc::clone(state), Rc::clone(easel));
+    move |paint: Paint| {
+        let easel = easel.borrow();
+        let caption = easel.caption();
+        let mut state = state.borrow_mut();
+        easel.borrow_canvas_context(|context| paint(&mut state, context, &caption));
+    }
+}
+
+/// Returns a function that changes the number shown, then draws it.
+fn changer(
+    state: &Rc<RefCell<State>>,
+    easel: &Rc<RefCell<Easel>>,
+) -> impl Fn(&dyn Fn(&mut State)) + Clone + 'static {
+    let paint = painter(state, easel);
+    move |update: &dyn Fn(&mut State)| {
+        paint(&|state, context, caption| state.change(context, caption, update));
+    }
+}
+
+/// Listens for changes to the speed, jumps to a number, and single steps.
+fn navigation_listeners(
+    navigation: &Rc<Navigation>,
+    state: &Rc<RefCell<State>>,
+    easel: &Rc<RefCell<Easel>>,
+) -> Result<Vec<Listener>> {
+    let change = changer(state, easel);
+
+    let speed = {
+        let (navigation, state) = (Rc::clone(navigation), Rc::clone(state));
+        move |_: Event| {
+            navigation.show_speed();
+            state.borrow_mut().speed = navigation.speed();
+        }
+    };
+
+    let target = {
+        let (navigation, change) = (Rc::clone(navigation), change.clone());
+        move |_: Event| {
+            if let Some(value) = navigation.target() {
+                change(&|state: &mut State| {
+                    state.histogram.set(&mut state.sieve, value);
+                });
+            }
+        }
+    };
+
+    let back = {
+        let change = change.clone();
+        move |_: Event| {
+            change(&|state: &mut State| {
+                state.histogram.decr(&mut state.sieve);
+            });
+        }
+    };
+
+    let forward = move |_: Event| {
+        change(&|state: &mut State| {
+            state.histogram.incr(&mut state.sieve);
+        });
+    };
+
+    Ok(vec![
+        Listener::new(navigation.speed_input(), "input", speed)?,
+        Listener::new(navigation.target_input(), "change", target)?,
+        Listener::new(&navigation.back, "click", back)?,
+        Listener::new(&navigation.forward, "click", forward)?,
+    ])
+}
+
+/// Listens for changes to the histogram's horizontal axis and scroll position.
+fn axis_listeners(
+    settings: &Rc<Settings>,
+    state: &Rc<RefCell<State>>,
+    easel: &Rc<RefCell<Easel>>,
+) -> Result<Vec<Listener>> {
+    let axis = {
+        let (settings, paint) = (Rc::clone(settings), painter(state, easel));
+        move |_: Event| {
+            let axis = settings.axis();
+            paint(&|state, context, caption| {
+                state.histogram.set_axis(axis);
+                state.redraw(context, caption);
+            });
+            settings.show_pan();
+            settings.save_axis();
+        }
+    };
+    let pan = {
+        let (settings, paint) = (Rc::clone(settings), painter(state, easel));
+        move |_: Event| {
+            let pan = settings.pan();
+            paint(&|state, context, caption| {
+                state.histogram.set_pan(pan);
+                state.redraw(context, caption);
+            });
+        }
+    };
+    Ok(vec![
+        Listener::new(settings.axis_select(), "change", axis)?,
+        Listener::new(settings.pan_input(), "input", pan)?,
+    ])
+}
 
 pub struct Chart {
-    easel: Easel,
+    root: Element,
+    easel: Rc<RefCell<Easel>>,
+    _listeners: Vec<Listener>,
 }
 
 impl Chart {
@@ -30,31 +240,76 @@ impl Chart {
     ///
     /// Will return [`Err`] if DOM interaction fails.
     pub fn new(system: &System) -> Result<Self> {
-        let mut sieve = rk_primes::Sieve::new();
+        let settings = Rc::new(Settings::new(system)?);
+        let navigation = Rc::new(Navigation::new(system)?);
         let mut histogram = Histogram::new();
-        let mut factors = Vec::new();
-        let easel = Easel::new(system, move |easel: RenderContext| {
-            // Repaint the canvas.
-            histogram.clear(easel.canvas);
-            histogram.incr(&mut sieve);
-            histogram.fill(easel.canvas, FillStyle::Color);
-            // Update the caption.
-            let value = histogram.value();
-            factors.clear();
-            factors.extend(sieve.factors(value));
-            let caption = format!("{value}: {factors:?}");
-            easel.caption.set_text_content(Some(&caption));
-        })?;
-        Ok(Chart { easel })
+        histogram.set_axis(settings.axis());
+        let state = Rc::new(RefCell::new(State {
+            sieve: Sieve::new(),
+            histogram,
+            style: settings.fill(),
+            view: settings.view(),
+            speed: navigation.speed(),
+            owed: 0.0,
+        }));
+        let easel = {
+            let state = Rc::clone(&state);
+            Easel::new(system, move |easel: RenderContext| {
+                let delta_ms = easel.delta_ms.unwrap_or_default();
+                state
+                    .borrow_mut()
+                    .advance(easel.canvas, easel.caption, delta_ms);
+            })?
+        };
+        let root = DIV
+            .class("primes")
+            .child3(easel.as_ref(), &navigation.root, &settings.root)
+            .to_element(system)?;
+        let easel = Rc::new(RefCell::new(easel));
+
+        let fill = {
+            let (settings, paint) = (Rc::clone(&settings), painter(&state, &easel));
+            move |_: Event| {
+                let style = settings.fill();
+                paint(&|state, context, caption| {
+                    state.style = style;
+                    state.redraw(context, caption);
+                });
+                settings.save_fill();
+            }
+        };
+        let view = {
+            let (settings, paint) = (Rc::clone(&settings), painter(&state, &easel));
+            move |_: Event| {
+                let view = settings.view();
+                paint(&|state, context, caption| {
+                    state.view = view;
+                    state.redraw(context, caption);
+                });
+                settings.show_pan();
+                settings.save_view();
+            }
+        };
+        let mut listeners = vec![
+            Listener::new(settings.fill_select(), "change", fill)?,
+            Listener::new(settings.view_select(), "change", view)?,
+        ];
+        listeners.extend(axis_listeners(&settings, &state, &easel)?);
+        listeners.extend(navigation_listeners(&navigation, &state, &easel)?);
+        Ok(Chart {
+            root,
+            easel,
+            _listeners: listeners,
+        })
     }
 
     pub fn play(&mut self) {
-        self.easel.play();
+        self.easel.borrow_mut().play();
     }
 }
 
 impl AsRef<Element> for Chart {
     fn as_ref(&self) -> &Element {
-        self.easel.as_ref()
+        &self.root
     }
 }
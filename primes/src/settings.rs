@@ -0,0 +1,200 @@
+//! Controls for how the chart is drawn, which are remembered between visits.
+
+use std::fmt::Display;
+
+use web_sys::{Element, HtmlInputElement, HtmlSelectElement, Storage};
+
+use easel::Result;
+use sugar::prelude::*;
+use system::System;
+
+use crate::{fill::FillStyle, histogram::Axis, view::View};
+
+/// The local storage key under which the fill style is saved.
+const FILL_KEY: &str = "primes.fill";
+
+/// The local storage key under which the view is saved.
+const VIEW_KEY: &str = "primes.view";
+
+/// The local storage key under which the histogram axis is saved.
+const AXIS_KEY: &str = "primes.axis";
+
+/// Creates a drop-down list of the specified choices.
+fn new_select(
+    system: &System,
+    class: &'static str,
+    title: &'static str,
+    choices: &[impl Display],
+) -> Result<Element> {
+    let select = SELECT
+        .class(class)
+        .attr("title", title)
+        .to_element(system)?;
+    for choice in choices {
+        let option = OPTION.to_element(system)?;
+        option.set_text_content(Some(&choice.to_string()));
+        select.append_with_node_1(&option)?;
+    }
+    Ok(select)
+}
+
+/// Returns the choice selected in a list created by [`new_select`].
+fn selected<T: Copy + Default>(select: &HtmlSelectElement, choices: &[T]) -> T {
+    usize::try_from(select.selected_index())
+        .ok()
+        .and_then(|index| choices.get(index).copied())
+        .unwrap_or_default()
+}
+
+/// Selects a choice in a list created by [`new_select`].
+fn select<T: PartialEq>(select: &HtmlSelectElement, choices: &[T], choice: &T) {
+    let index = choices.iter().position(|c| c == choice).unwrap_or_default();
+    select.set_selected_index(i32::try_from(index).unwrap_or_default());
+}
+
+pub struct Settings {
+    pub root: Element,
+    fill: HtmlSelectElement,
+    view: HtmlSelectElement,
+    axis: HtmlSelectElement,
+    /// Scrolls the histogram along the full axis.
+    pan: HtmlInputElement,
+    storage: Option<Storage>,
+}
+
+impl Settings {
+    /// Creates the controls, showing the saved settings, if any.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Err`] if DOM interaction fails.
+    pub fn new(system: &System) -> Result<Self> {
+        let view = new_select(system, "primes-view", "How to draw the numbers", &View::ALL)?;
+        let fill = new_select(
+            system,
+            "primes-fill",
+            "Colors of the prime factors",
+            &FillStyle::ALL,
+        )?;
+        let axis = new_select(
+            system,
+            "primes-axis",
+            "Which primes get columns",
+            &Axis::ALL,
+        )?;
+        let pan = INPUT
+            .class("primes-pan")
+            .attr("type", "range")
+            .attr("min", "0")
+            .attr("max", "1")
+            .attr("step", "any")
+            .attr("value", "0")
+            .attr("title", "Scroll the columns")
+            .to_element(system)?;
+        let root = DIV
+            .class("primes-settings")
+            .child3(
+                &view,
+                DIV.class("primes-settings__group").child2(&axis, &pan),
+                &fill,
+            )
+            .to_element(system)?;
+        // Storage may be disabled, in which case settings last only as long
+        // as the page.
+        let storage = system.window.local_storage().ok().flatten();
+        let settings = Settings {
+            root,
+            fill: fill.dyn_cast()?,
+            view: view.dyn_cast()?,
+            axis: axis.dyn_cast()?,
+            pan: pan.dyn_cast()?,
+            storage,
+        };
+        let style = settings
+            .load(FILL_KEY)
+            .and_then(|key| FillStyle::from_key(&key))
+            .unwrap_or_default();
+        select(&settings.fill, &FillStyle::ALL, &style);
+        let view = settings
+            .load(VIEW_KEY)
+            .and_then(|key| View::from_key(&key))
+            .unwrap_or_default();
+        select(&settings.view, &View::ALL, &view);
+        let axis = settings
+            .load(AXIS_KEY)
+            .and_then(|key| Axis::from_key(&key))
+            .unwrap_or_default();
+        select(&settings.axis, &Axis::ALL, &axis);
+        settings.show_pan();
+        Ok(settings)
+    }
+
+    pub fn fill_select(&self) -> &HtmlSelectElement {
+        &self.fill
+    }
+
+    pub fn view_select(&self) -> &HtmlSelectElement {
+        &self.view
+    }
+
+    pub fn axis_select(&self) -> &HtmlSelectElement {
+        &self.axis
+    }
+
+    pub fn pan_input(&self) -> &HtmlInputElement {
+        &self.pan
+    }
+
+    pub fn fill(&self) -> FillStyle {
+        selected(&self.fill, &FillStyle::ALL)
+    }
+
+    pub fn view(&self) -> View {
+        selected(&self.view, &View::ALL)
+    }
+
+    pub fn axis(&self) -> Axis {
+        selected(&self.axis, &Axis::ALL)
+    }
+
+    /// Returns how far to scroll the full axis, from 0 to 1.
+    pub fn pan(&self) -> f64 {
+        let pan = self.pan.value_as_number();
+        if pan.is_finite() { pan } else { 0.0 }
+    }
+
+    /// Shows the scroll bar only while it would have an effect: for a
+    /// histogram along the full axis.
+    pub fn show_pan(&self) {
+        let show = self.view() == View::Histogram && self.axis() == Axis::Full;
+        _ = self.pan.toggle_attribute_with_force("hidden", !show);
+    }
+
+    fn load(&self, key: &str) -> Option<String> {
+        self.storage
+            .as_ref()
+            .and_then(|storage| storage.get_item(key).ok().flatten())
+    }
+
+    fn save(&self, key: &str, value: &str) {
+        if let Some(storage) = &self.storage {
+            // Failing to save, as when storage is full, costs only the setting.
+            _ = storage.set_item(key, value);
+        }
+    }
+
+    /// Saves the chosen fill style for later visits.
+    pub fn save_fill(&self) {
+        self.save(FILL_KEY, self.fill().key());
+    }
+
+    /// Saves the chosen view for later visits.
+    pub fn save_view(&self) {
+        self.save(VIEW_KEY, self.view().key());
+    }
+
+    /// Saves the chosen histogram axis for later visits.
+    pub fn save_axis(&self) {
+        self.save(AXIS_KEY, self.axis().key());
+    }
+}
@@ -0,0 +1,191 @@
+//! Spirals that place each positive integer at its own point on the plane, so
+//! that the primes trace out patterns.
+
+use std::{f64::consts::TAU, ops::RangeInclusive};
+
+use easel::canvas_size;
+use rk_primes::Sieve;
+use system::SizeU32;
+use web_sys::CanvasRenderingContext2d;
+
+/// Pixels between the centers of adjacent cells in the Ulam spiral, and
+/// between successive turns of the Sacks spiral.
+const PITCH: f64 = 4.0;
+
+/// Width of the square dot drawn for each number.
+const DOT: f64 = 3.0;
+
+/// Color of the primes, which stand out against the composites.
+const PRIME: &str = "#FFFFFF";
+
+/// Returns the cell of `n` in the Ulam spiral, with 1 at the origin, 2 to its
+/// right, and the spiral winding counterclockwise from there.
+///
+/// # Panics
+///
+/// Will panic if `n` is zero.
+pub fn ulam(n: u32) -> (i32, i32) {
+    assert!(n > 0, "the spiral starts at 1");
+    // Ring k is the boundary of the square of side 2k + 1 centered on the
+    // origin, ending at the number (2k + 1)².
+    let k = (n - 1).isqrt().div_ceil(2);
+    if k == 0 {
+        return (0, 0);
+    }
+    // Distance along the ring, starting just above its lower right corner.
+    let d = (n - 1 - (2 * k - 1).pow(2)).cast_signed();
+    let k = k.cast_signed();
+    match d / (2 * k) {
+        0 => (k, d - k + 1),
+        1 => (3 * k - 1 - d, k),
+        2 => (-k, 5 * k - 1 - d),
+        _ => (d - 7 * k + 1, -k),
+    }
+}
+
+/// Returns the point of `n` on the Sacks spiral, an Archimedean spiral on
+/// which each perfect square completes a turn, landing on the positive x-axis.
+pub fn sacks(n: u32) -> (f64, f64) {
+    let r = f64::from(n).sqrt();
+    let theta = TAU * r;
+    (r * theta.cos(), r * theta.sin())
+}
+
+/// Returns the color of `n`, highlighting primes, and shading composites more
+/// brightly the more prime factors (counted with multiplicity) they have.
+fn shade(sieve: &mut Sieve, n: u32) -> Option<String> {
+    match sieve.factors(n).count() {
+        0 => None,
+        1 => Some(PRIME.to_owned()),
+        count => Some(format!("hsl(0, 0%, {}%)", (count * 5 + 10).min(40))),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Spiral {
+    Ulam,
+    Sacks,
+}
+
+impl Spiral {
+    /// Returns the center of the dot for `n`, in pixels from the center of the
+    /// canvas.
+    fn offset(self, n: u32) -> (f64, f64) {
+        let (x, y) = match self {
+            Spiral::Ulam => {
+                let (x, y) = ulam(n);
+                (f64::from(x), f64::from(y))
+            }
+            Spiral::Sacks => sacks(n),
+        };
+        (x * PITCH, y * PITCH)
+    }
+
+    /// Returns the greatest number that fits on a canvas of the specified
+    /// size. Larger numbers need not be drawn.
+    fn capacity(self, canvas: SizeU32) -> u32 {
+        // Both spirals reach about one pitch farther from the origin per ring.
+        let rings = canvas.width.min(canvas.height) / 8;
+        match self {
+            Spiral::Ulam => (2 * rings + 1).saturating_pow(2),
+            Spiral::Sacks => rings.saturating_pow(2),
+        }
+    }
+
+    /// Calls `f` with the top left corner of the dot for each number in the
+    /// range that fits on the canvas.
+    fn dots(
+        self,
+        context: &CanvasRenderingContext2d,
+        numbers: RangeInclusive<u32>,
+        mut f: impl FnMut(u32, f64, f64),
+    ) {
+        let Some(canvas) = context.canvas() else {
+            return;
+        };
+        let size = canvas_size(&canvas);
+        let center_x = f64::from(size.width) / 2.0;
+        let center_y = f64::from(size.height) / 2.0;
+        let (start, end) = numbers.into_inner();
+        for n in start.max(1)..=end.min(self.capacity(size)) {
+            let (x, y) = self.offset(n);
+            f(n, center_x + x - DOT / 2.0, center_y + y - DOT / 2.0);
+        }
+    }
+
+    /// Draws the specified numbers.
+    pub fn draw(
+        self,
+        context: &CanvasRenderingContext2d,
+        sieve: &mut Sieve,
+        numbers: RangeInclusive<u32>,
+    ) {
+        self.dots(context, numbers, |n, x, y| {
+            if let Some(color) = shade(sieve, n) {
+                context.set_fill_style_str(&color);
+                context.fill_rect(x, y, DOT, DOT);
+            }
+        });
+    }
+
+    /// Erases the specified numbers.
+    pub fn erase(self, context: &CanvasRenderingContext2d, numbers: RangeInclusive<u32>) {
+        self.dots(context, numbers, |_, x, y| {
+            context.clear_rect(x, y, DOT, DOT);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ulam_winds_counterclockwise() {
+        let cells: Vec<_> = (1..=10).map(ulam).collect();
+        assert_eq!(
+            cells,
+            [
+                (0, 0),
+                (1, 0),
+                (1, 1),
+                (0, 1),
+                (-1, 1),
+                (-1, 0),
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (2, -1),
+            ]
+        );
+    }
+
+    #[test]
+    fn ulam_fills_squares_one_step_at_a_time() {
+        let k: i32 = 6;
+        let last = (2 * k + 1).pow(2);
+        let cells: Vec<_> = (1..=last.cast_unsigned()).map(ulam).collect();
+        for pair in cells.windows(2) {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            assert_eq!((x1 - x0).abs() + (y1 - y0).abs(), 1, "{pair:?}");
+        }
+        let mut sorted = cells.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(sorted.len(), cells.len());
+        assert!(cells.iter().all(|&(x, y)| x.abs() <= k && y.abs() <= k));
+        assert_eq!(cells.last(), Some(&(k, -k)));
+    }
+
+    #[test]
+    fn sacks_puts_squares_on_the_x_axis() {
+        for n in 1..=400 {
+            let (x, y) = sacks(n);
+            let r = f64::from(n).sqrt();
+            assert!((x.hypot(y) - r).abs() < 1e-9, "{n}");
+            if r.fract() == 0.0 {
+                assert!((x - r).abs() < 1e-9 && y.abs() < 1e-9, "{n}: ({x}, {y})");
+            }
+        }
+    }
+}
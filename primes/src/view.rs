@@ -0,0 +1,50 @@
+use std::fmt;
+
+use crate::spiral::Spiral;
+
+/// The ways of drawing the numbers on the canvas.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum View {
+    /// Columns of bricks, one column per prime, stacked as high as its
+    /// exponent in the current number.
+    #[default]
+    Histogram,
+    Ulam,
+    Sacks,
+}
+
+impl View {
+    pub const ALL: [View; 3] = [View::Histogram, View::Ulam, View::Sacks];
+
+    /// Returns the name under which the view is saved.
+    pub fn key(self) -> &'static str {
+        match self {
+            View::Histogram => "histogram",
+            View::Ulam => "ulam",
+            View::Sacks => "sacks",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<View> {
+        View::ALL.into_iter().find(|view| view.key() == key)
+    }
+
+    /// Returns the spiral drawn by this view, if it is one.
+    pub fn spiral(self) -> Option<Spiral> {
+        match self {
+            View::Histogram => None,
+            View::Ulam => Some(Spiral::Ulam),
+            View::Sacks => Some(Spiral::Sacks),
+        }
+    }
+}
+
+impl fmt::Display for View {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            View::Histogram => "Histogram",
+            View::Ulam => "Ulam spiral",
+            View::Sacks => "Sacks spiral",
+        })
+    }
+}
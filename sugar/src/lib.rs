@@ -1,10 +1,13 @@
 pub mod js;
+mod listener;
 
 use wasm_bindgen::JsValue;
 use web_sys::Element;
 
 use system::System;
 
+pub use listener::Listener;
+
 type Result<T> = std::result::Result<T, JsValue>;
 
 pub trait ToElement {
@@ -209,10 +212,14 @@ pub mod prelude {
     pub const H2: Tag = Tag("h2");
     pub const DIV: Tag = Tag("div");
     pub const HEADER: Tag = Tag("header");
+    pub const INPUT: Tag = Tag("input");
     pub const LI: Tag = Tag("li");
     pub const MAIN: Tag = Tag("main");
     pub const NAV: Tag = Tag("nav");
+    pub const OPTGROUP: Tag = Tag("optgroup");
+    pub const OPTION: Tag = Tag("option");
     pub const SPAN: Tag = Tag("span");
     pub const P: Tag = Tag("p");
+    pub const SELECT: Tag = Tag("select");
     pub const UL: Tag = Tag("ul");
 }
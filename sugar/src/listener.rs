@@ -0,0 +1,43 @@
+//! Event listeners that detach themselves from their targets when dropped.
+
+use wasm_bindgen::prelude::*;
+use web_sys::EventTarget;
+
+pub struct Listener {
+    target: EventTarget,
+    kind: &'static str,
+    callback: Closure<dyn FnMut(JsValue)>,
+}
+
+impl Listener {
+    /// Calls `handler` with each event of the specified kind (e.g., "click")
+    /// dispatched to the target, until this listener is dropped. The events
+    /// are cast to `E` without checking, so `E` must match `kind`.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Err`] if the listener cannot be added to the target.
+    pub fn new<E: JsCast>(
+        target: &EventTarget,
+        kind: &'static str,
+        mut handler: impl FnMut(E) + 'static,
+    ) -> Result<Listener, JsValue> {
+        let callback = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            handler(event.unchecked_into());
+        });
+        target.add_event_listener_with_callback(kind, callback.as_ref().unchecked_ref())?;
+        Ok(Listener {
+            target: target.clone(),
+            kind,
+            callback,
+        })
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        _ = self
+            .target
+            .remove_event_listener_with_callback(self.kind, self.callback.as_ref().unchecked_ref());
+    }
+}
@@ -23,6 +23,13 @@ pub const fn f64_to_u32_saturating(value: f64) -> u32 {
     value as u32
 }
 
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub const fn f64_to_i32_saturating(value: f64) -> i32 {
+    // See the comment in `f64_to_u32_saturating`.
+    value as i32
+}
+
 /// # Panics
 ///
 /// Will panic on overflow.
@@ -48,7 +55,7 @@ pub struct SizeF64 {
 /// to be integers helps avoid subtle issues, such as rendering artifacts on
 /// canvas elements. This struct therefore represents numbers as `u32`, which
 /// (unlike `usize`) is `Into<f64>`.
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub struct SizeU32 {
     pub height: u32,
     pub width: u32,